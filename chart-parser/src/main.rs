@@ -11,9 +11,12 @@ fn main() {
     use std::io::{BufWriter, Write};
     use itertools::Itertools;
     use kxparser::domain::models::{
-        grammar::{Rule},
+        grammar::{Grammar, Rule},
     };
-    use kxparser::domain::services::chart_parsing::{earley, parse};
+    use kxparser::domain::services::chart_parsing::{earley, parse, Algorithm};
+    use kxparser::domain::services::cky_parsing::cky;
+    use kxparser::domain::services::semiring_parsing::count_trees;
+    use kxparser::domain::services::forest::parse_forest;
     use kxparser::utilities::container::leftcorners_dict;
 
     let grammar = match parser::read_grammar_from_ron_file("grammar.ron") {
@@ -28,29 +31,32 @@ fn main() {
                         String::from("NP"),
                         String::from("VP")
                     ),
+                    weight: None,
                 },
                 Rule {
                     lhs: String::from("VP"),
-                    rhs: vec!(String::from("Verb"),)
+                    rhs: vec!(String::from("Verb"),),
+                    weight: None,
                 },
                 Rule {
                     lhs: String::from("VP"),
-                    rhs: vec!(String::from("Verb"), String::from("NP")) },
-                Rule { lhs: String::from("VP"),   rhs: vec!(String::from("VP"), String::from("PP")) },
-                Rule { lhs: String::from("NP"),   rhs: vec!(String::from("Det"), String::from("Noun")) },
-                Rule { lhs: String::from("NP"),   rhs: vec!(String::from("NP"), String::from("PP")) },
-                Rule { lhs: String::from("PP"),   rhs: vec!(String::from("Prep"), String::from("NP")) },
-                Rule { lhs: String::from("Verb"), rhs: vec!(String::from("sees"),) },
-                Rule { lhs: String::from("Det"),  rhs: vec!(String::from("the"),) },
-                Rule { lhs: String::from("Det"),  rhs: vec!(String::from("a"),) },
-                Rule { lhs: String::from("Prep"), rhs: vec!(String::from("under"),) },
-                Rule { lhs: String::from("Prep"), rhs: vec!(String::from("with"),) },
-                Rule { lhs: String::from("Prep"), rhs: vec!(String::from("in"),) },
-                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("zebra"),) },
-                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("lion"),) },
-                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("tree"),) },
-                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("park"),) },
-                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("telescope"),) },
+                    rhs: vec!(String::from("Verb"), String::from("NP")),
+                    weight: None },
+                Rule { lhs: String::from("VP"),   rhs: vec!(String::from("VP"), String::from("PP")), weight: None },
+                Rule { lhs: String::from("NP"),   rhs: vec!(String::from("Det"), String::from("Noun")), weight: None },
+                Rule { lhs: String::from("NP"),   rhs: vec!(String::from("NP"), String::from("PP")), weight: None },
+                Rule { lhs: String::from("PP"),   rhs: vec!(String::from("Prep"), String::from("NP")), weight: None },
+                Rule { lhs: String::from("Verb"), rhs: vec!(String::from("sees"),), weight: None },
+                Rule { lhs: String::from("Det"),  rhs: vec!(String::from("the"),), weight: None },
+                Rule { lhs: String::from("Det"),  rhs: vec!(String::from("a"),), weight: None },
+                Rule { lhs: String::from("Prep"), rhs: vec!(String::from("under"),), weight: None },
+                Rule { lhs: String::from("Prep"), rhs: vec!(String::from("with"),), weight: None },
+                Rule { lhs: String::from("Prep"), rhs: vec!(String::from("in"),), weight: None },
+                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("zebra"),), weight: None },
+                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("lion"),), weight: None },
+                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("tree"),), weight: None },
+                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("park"),), weight: None },
+                Rule { lhs: String::from("Noun"), rhs: vec!(String::from("telescope"),), weight: None },
             )
         }
     };
@@ -129,6 +135,27 @@ fn main() {
     );
     println!("earley2, elapsed time: {:.6?}", now.elapsed());
 
+    let sentence = parser::example(3);
+    for algorithm in &[Algorithm::LeftCorner, Algorithm::TopDown] {
+        let chart = algorithm.chart(&grammar, "S", &sentence);
+        println!("{:?}, chart size: {}", algorithm, chart.chartsize());
+    }
+
+    let cnf = Grammar::from_rules(grammar.clone()).to_cnf();
+    let now = Instant::now();
+    let chart = cky(&cnf, &sentence);
+    println!("cky, chart size: {}, elapsed time: {:.6?}", chart.chartsize(), now.elapsed());
+    println!("number of trees: {}", count_trees(&earley(&grammar, &sentence), "S"));
+
+    let now = Instant::now();
+    let forest = parse_forest(&grammar, "S", &parser::example(9));
+    println!(
+        "forest for example(9): {} nodes, {} trees, elapsed time: {:.6?}",
+        forest.nodes().len(),
+        forest.count_trees(),
+        now.elapsed(),
+    );
+
     for tree in parse(&grammar, "S", &parser::example(3)).iter() {
         println!("{}", tree);
     }
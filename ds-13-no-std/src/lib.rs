@@ -0,0 +1,61 @@
+//! Uses ds-13 from a `no_std` crate.
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use ds_13::stats::measure;
+use ds_13::sync;
+use ds_13::traits::Foldable;
+use ds_13::unsync::lazy_list::LazyList;
+use ds_13::unsync::rb_map::RBMap;
+use ds_13::unsync::tree::Tree;
+use ds_13::unsync::List;
+
+/// Returns the first `n` squares, in descending order.
+pub fn squares(n: u32) -> List<u32> {
+    (0..n).fold(List::new(), |list, x| list.pushed_front(x * x))
+}
+
+/// Counts the occurrences of every word.
+pub fn word_counts<'a>(words: &[&'a str]) -> RBMap<&'a str, usize> {
+    words.iter().fold(RBMap::new(), |counts, word| {
+        let count = counts.get(word).copied().unwrap_or(0);
+        counts.inserted_or_replaced(word, count + 1)
+    })
+}
+
+pub fn tree_size<T>(tree: &Tree<T>) -> usize {
+    tree.length()
+}
+
+pub fn synced_sum(list: &sync::list::List<u32>) -> u32 {
+    list.foldl(|acc, x| acc + x, 0)
+}
+
+pub fn naturals(n: usize) -> Vec<usize> {
+    LazyList::iterate(0, |x| x + 1).take(n).iter().cloned().collect()
+}
+
+pub fn shared_nodes(list: &List<u32>) -> usize {
+    measure(&[list, &list.pushed_front(0)]).shared_nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collections_work_without_std() {
+        let list = squares(3);
+        assert_eq!(list.front(), Some(&4));
+        assert_eq!(shared_nodes(&list), 3);
+
+        let counts = word_counts(&["a", "b", "a"]);
+        assert_eq!(counts.get(&"a"), Some(&2));
+
+        assert_eq!(tree_size(&Tree::leaf(1)), 1);
+        assert_eq!(synced_sum(&sync::list::List::from_value(3).pushed_front(2)), 5);
+        assert_eq!(naturals(3), vec!(0, 1, 2));
+    }
+}
@@ -0,0 +1,82 @@
+//! Compares `List` and `ChunkedList`.
+//!
+//! Run with `cargo bench -p ds-13 --bench chunked_list`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ds_13::stats::measure;
+use ds_13::unsync::chunked_list::ChunkedList;
+use ds_13::unsync::List;
+
+const N: usize = 1_000_000;
+const ROUNDS: u32 = 10;
+
+/// Returns the mean duration of `f` over `ROUNDS` runs.
+fn time<R>(mut f: impl FnMut() -> R) -> Duration {
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn report(name: &str, list: Duration, chunked: Duration) {
+    println!(
+        "{:<12} {:>12.2?} {:>12.2?} {:>8.2}x",
+        name,
+        list,
+        chunked,
+        list.as_secs_f64() / chunked.as_secs_f64()
+    );
+}
+
+fn build_list() -> List<usize> {
+    (0..N).fold(List::new(), |list, x| list.pushed_front(x))
+}
+
+fn build_chunked() -> ChunkedList<usize> {
+    (0..N).fold(ChunkedList::new(), |list, x| list.pushed_front(x))
+}
+
+fn main() {
+    let list = build_list();
+    let chunked = build_chunked();
+
+    println!("{:<12} {:>12} {:>12} {:>9}", "n = 10^6", "List", "ChunkedList", "speedup");
+    report("build", time(build_list), time(build_chunked));
+    report(
+        "iter sum",
+        time(|| list.iter().sum::<usize>()),
+        time(|| chunked.iter().sum::<usize>()),
+    );
+    report("len", time(|| list.len()), time(|| chunked.len()));
+    report("nth", time(|| list.nth(N / 2).copied()), time(|| chunked.nth(N / 2).copied()));
+    report(
+        "pop all",
+        time(|| {
+            let mut l = list.clone();
+            while !l.is_empty() {
+                l = l.popped_front();
+            }
+        }),
+        time(|| {
+            let mut l = chunked.clone();
+            while !l.is_empty() {
+                l = l.popped_front();
+            }
+        }),
+    );
+    report(
+        "sort",
+        time(|| list.sort_by(|a, b| b.cmp(a))),
+        time(|| chunked.sort_by(|a, b| b.cmp(a))),
+    );
+
+    println!();
+    println!(
+        "heap bytes   {:>12} {:>12}",
+        measure(&[&list]).heap_bytes,
+        measure(&[&chunked]).heap_bytes
+    );
+}
@@ -0,0 +1,386 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use bumpalo::Bump;
+use crate::Error;
+
+/// A bump allocator for the nodes of arena-backed structures.
+///
+/// Only `ArenaList` allocates in an arena; `List`, `RBMap` and the
+/// other persistent structures keep their `Rc` nodes.
+///
+/// Allocating a node is a pointer bump, and all nodes are freed at
+/// once when the arena is dropped or reset. The destructors of the
+/// elements are never run, so elements that own heap memory, e.g.
+/// `String`s, leak it.
+pub struct Arena {
+    bump: Bump,
+}
+
+impl Arena {
+    pub fn new() -> Self {
+        Arena { bump: Bump::new() }
+    }
+
+    /// Creates an arena with room for at least `bytes` bytes
+    /// before it has to allocate another chunk.
+    pub fn with_capacity(bytes: usize) -> Self {
+        Arena { bump: Bump::with_capacity(bytes) }
+    }
+
+    /// Returns the number of bytes allocated by the arena, including
+    /// space that isn't used yet.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Frees all nodes, keeping the largest chunk for reuse.
+    ///
+    /// Takes `&mut self`, so no structure can still borrow the nodes.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+
+    fn alloc<T>(&self, value: T) -> &T {
+        self.bump.alloc(value)
+    }
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A persistent singly linked list whose nodes live in an `Arena`.
+///
+/// The list is a single reference, so it is `Copy`, and dropping it
+/// frees nothing.
+pub struct ArenaList<'a, T> {
+    head: Link<'a, T>,
+}
+
+type Link<'a, T> = Option<&'a Node<'a, T>>;
+
+struct Node<'a, T> {
+    element: T,
+    next: Link<'a, T>,
+}
+
+impl<'a, T> ArenaList<'a, T> {
+    pub fn new() -> Self {
+        ArenaList { head: None }
+    }
+
+    /// Creates a list with `element` in front of `tail`, allocating
+    /// the new node in `arena`.
+    ///
+    /// Complexity: O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::arena::{Arena, ArenaList};
+    ///
+    /// let arena = Arena::new();
+    /// let tail = ArenaList::from_value(&arena, 2);
+    /// let list = ArenaList::cons(&arena, 1, tail);
+    ///
+    /// assert_eq!(list.iter().collect::<Vec<_>>(), vec!(&1, &2));
+    /// assert!(list.popped_front().ptr_eq(&tail));
+    /// ```
+    pub fn cons(arena: &'a Arena, element: T, tail: Self) -> Self {
+        ArenaList { head: Some(arena.alloc(Node { element, next: tail.head })) }
+    }
+
+    pub fn from_value(arena: &'a Arena, element: T) -> Self {
+        ArenaList::cons(arena, element, ArenaList::new())
+    }
+
+    /// Creates a list with the elements in iteration order.
+    pub fn from_iter_in(arena: &'a Arena, iter: impl IntoIterator<Item = T>) -> Self {
+        let elements: Vec<T> = iter.into_iter().collect();
+        prepended_all(arena, elements.into_iter(), ArenaList::new())
+    }
+
+    pub fn pushed_front(&self, arena: &'a Arena, element: T) -> Self {
+        ArenaList::cons(arena, element, *self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (self.head, other.head) {
+            (Some(x), Some(y)) => core::ptr::eq(x, y),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn front(&self) -> Option<&'a T> {
+        self.head.map(|node| &node.element)
+    }
+
+    /// Returns the tail of the list.
+    ///
+    /// # Panics
+    ///
+    /// This method panics when called on an empty list.
+    pub fn popped_front(&self) -> Self {
+        match self.try_popped_front() {
+            Err(_) => panic!("You can't pop an empty list!"),
+            Ok(tail) => tail,
+        }
+    }
+
+    /// Returns the tail of the list, or `Error::EmptyList` if the
+    /// list is empty.
+    pub fn try_popped_front(&self) -> Result<Self, Error> {
+        match self.head {
+            None => Err(Error::EmptyList),
+            Some(node) => Ok(ArenaList { head: node.next }),
+        }
+    }
+
+    /// Complexity: O(n)
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter { next: self.head }
+    }
+
+    /// Returns a list with `f` applied to every element.
+    pub fn fmap<U>(&self, arena: &'a Arena, f: impl FnMut(&T) -> U) -> ArenaList<'a, U> {
+        let elements: Vec<U> = self.iter().map(f).collect();
+        prepended_all(arena, elements.into_iter(), ArenaList::new())
+    }
+
+    /// Returns the concatenation of the lists that `f` returns for
+    /// the elements, sharing the last non-empty one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::arena::{Arena, ArenaList};
+    ///
+    /// let arena = Arena::new();
+    /// let list = ArenaList::from_iter_in(&arena, vec!(1, 2));
+    /// let result = list.and_then(&arena, |x| ArenaList::from_iter_in(&arena, vec!(*x, *x * 10)));
+    ///
+    /// assert_eq!(result.iter().collect::<Vec<_>>(), vec!(&1, &10, &2, &20));
+    /// ```
+    pub fn and_then<U: Clone>(
+        &self,
+        arena: &'a Arena,
+        f: impl FnMut(&T) -> ArenaList<'a, U>,
+    ) -> ArenaList<'a, U> {
+        ArenaList::concat(arena, self.iter().map(f))
+    }
+}
+
+impl<'a, T: Clone> ArenaList<'a, T> {
+    /// Returns the elements of `self` followed by `tail`, sharing
+    /// `tail`.
+    ///
+    /// Complexity: O(n), where n is the length of `self`
+    pub fn appended(&self, arena: &'a Arena, tail: Self) -> Self {
+        if tail.is_empty() {
+            return *self;
+        }
+        let elements: Vec<T> = self.iter().cloned().collect();
+        prepended_all(arena, elements.into_iter(), tail)
+    }
+
+    /// Returns the concatenation of `lists`, sharing the last
+    /// non-empty one.
+    pub fn concat(arena: &'a Arena, lists: impl IntoIterator<Item = Self>) -> Self {
+        let mut lists: Vec<Self> = lists.into_iter().filter(|list| !list.is_empty()).collect();
+        match lists.pop() {
+            None => ArenaList::new(),
+            Some(last) => lists.iter().rev().fold(last, |acc, list| list.appended(arena, acc)),
+        }
+    }
+
+    pub fn reversed(&self, arena: &'a Arena) -> Self {
+        self.iter().fold(ArenaList::new(), |acc, x| acc.pushed_front(arena, x.clone()))
+    }
+}
+
+fn prepended_all<'a, T>(
+    arena: &'a Arena,
+    elements: impl DoubleEndedIterator<Item = T>,
+    tail: ArenaList<'a, T>,
+) -> ArenaList<'a, T> {
+    elements.rev().fold(tail, |acc, x| ArenaList::cons(arena, x, acc))
+}
+
+pub struct Iter<'a, T> {
+    next: Link<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next;
+            &node.element
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for ArenaList<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &ArenaList<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Clone for ArenaList<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ArenaList<'_, T> {}
+
+impl<T> Default for ArenaList<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for ArenaList<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for ArenaList<'_, T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ArenaList<'_, T> {}
+
+impl<T> PartialOrd for ArenaList<'_, T>
+where
+    T: PartialOrd,
+{
+    /// Compares the lists lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ArenaList<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for ArenaList<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for x in self {
+            x.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cons_shares_tail() {
+        let arena = Arena::new();
+        let tail = ArenaList::from_iter_in(&arena, vec!(2, 3));
+
+        let l1 = tail.pushed_front(&arena, 1);
+        let l2 = tail.pushed_front(&arena, 0);
+
+        assert_eq!(l1.len(), 3);
+        assert!(l1.popped_front().ptr_eq(&l2.popped_front()));
+        assert_eq!(l2.front(), Some(&0));
+    }
+
+    #[test]
+    fn popped_front_on_empty_list() {
+        assert_eq!(ArenaList::<i32>::new().try_popped_front(), Err(Error::EmptyList));
+    }
+
+    #[test]
+    #[should_panic]
+    fn popped_front_on_empty_list_panics() {
+        ArenaList::<i32>::new().popped_front();
+    }
+
+    #[test]
+    fn fmap_appended_and_reversed() {
+        let arena = Arena::new();
+        let list = ArenaList::from_iter_in(&arena, 1..4);
+        let tail = ArenaList::from_value(&arena, 4);
+
+        let appended = list.appended(&arena, tail);
+
+        assert_eq!(appended, ArenaList::from_iter_in(&arena, 1..5));
+        assert!(appended.popped_front().popped_front().popped_front().ptr_eq(&tail));
+        assert_eq!(format!("{:?}", list.fmap(&arena, |x| x * 2)), "[2, 4, 6]");
+        assert_eq!(list.reversed(&arena), ArenaList::from_iter_in(&arena, vec!(3, 2, 1)));
+    }
+
+    #[test]
+    fn and_then_shares_last_list() {
+        let arena = Arena::new();
+        let last = ArenaList::from_value(&arena, 'c');
+        let list = ArenaList::from_iter_in(&arena, vec!(1, 2, 3));
+
+        let result = list.and_then(&arena, |x| match x {
+            1 => ArenaList::from_iter_in(&arena, vec!('a', 'b')),
+            2 => last,
+            _ => ArenaList::new(),
+        });
+
+        assert_eq!(result.iter().collect::<String>(), "abc");
+        assert!(result.popped_front().popped_front().ptr_eq(&last));
+    }
+
+    #[test]
+    fn reset_frees_nodes() {
+        let mut arena = Arena::with_capacity(1 << 10);
+        {
+            let list = ArenaList::from_iter_in(&arena, 0..100_000_i64);
+            assert_eq!(list.iter().sum::<i64>(), 4_999_950_000);
+        }
+        let allocated = arena.allocated_bytes();
+        arena.reset();
+
+        assert!(arena.allocated_bytes() <= allocated);
+    }
+}
@@ -0,0 +1,42 @@
+use core::fmt;
+
+/// The error returned by the checked (`try_`) variants of partial
+/// operations, whose unchecked variants panic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The operation needs a non-empty list.
+    EmptyList,
+    /// The operation needs a non-empty tree.
+    EmptyTree,
+    /// The index is not less than the length.
+    IndexOutOfBounds { index: usize, len: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::EmptyList => write!(f, "the list is empty"),
+            Error::EmptyTree => write!(f, "the tree is empty"),
+            Error::IndexOutOfBounds { index, len } => {
+                write!(f, "index {} out of bounds for length {}", index, len)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_describes_error() {
+        assert_eq!(Error::EmptyList.to_string(), "the list is empty");
+        assert_eq!(
+            Error::IndexOutOfBounds { index: 3, len: 2 }.to_string(),
+            "index 3 out of bounds for length 2"
+        );
+    }
+}
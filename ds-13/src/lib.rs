@@ -1,2 +1,22 @@
+//! Persistent data structures.
+//!
+//! The crate is `no_std` (but needs `alloc`) when the default `std`
+//! feature is disabled. The `unsync::interner` module needs `std`.
+//!
+//! The optional `arena` feature adds the `arena` module, with lists
+//! whose nodes are bump-allocated and freed all at once. The other
+//! structures, e.g. `List` and `RBMap`, always allocate their nodes
+//! with `Rc` or `Arc`; the arena isn't a pluggable allocator for them.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 pub mod unsync;
 pub mod sync;
+pub mod stats;
+pub mod traits;
+pub mod error;
+#[cfg(feature = "arena")]
+pub mod arena;
+
+pub use error::Error;
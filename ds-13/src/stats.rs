@@ -0,0 +1,230 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::mem;
+
+/// Memory and sharing statistics for one or more versions of
+/// persistent collections.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of distinct heap nodes reachable from the versions.
+    pub nodes: usize,
+    /// Estimated heap bytes used by the distinct nodes.
+    ///
+    /// Only the node allocations themselves (including the reference
+    /// counts) are counted, not heap memory owned by the elements.
+    pub heap_bytes: usize,
+    /// Number of distinct nodes reachable from two or more versions.
+    pub shared_nodes: usize,
+}
+
+/// Implemented by collections that can report their heap nodes.
+pub trait Measure {
+    /// Reports every node reachable from `self` to `visitor`.
+    fn visit_nodes(&self, visitor: &mut NodeVisitor);
+}
+
+/// Collects the nodes reported by `Measure` implementations,
+/// deduplicated by pointer.
+#[derive(Debug, Default)]
+pub struct NodeVisitor {
+    seen: BTreeSet<usize>,
+    versions: BTreeMap<usize, (usize, usize)>,
+}
+
+impl NodeVisitor {
+    fn new() -> Self {
+        NodeVisitor::default()
+    }
+
+    /// Registers an `Rc` node.
+    ///
+    /// Returns `true` if the node has not been seen before in the
+    /// current version, i.e. if the caller should visit its children.
+    pub fn visit<N>(&mut self, node: &Rc<N>) -> bool {
+        // An `RcBox` holds a strong and a weak count besides the value.
+        self.register(
+            Rc::as_ptr(node) as *const () as usize,
+            mem::size_of::<N>() + 2 * mem::size_of::<usize>(),
+        )
+    }
+
+    /// Registers an `Arc` node, see `visit`.
+    pub fn visit_arc<N>(&mut self, node: &Arc<N>) -> bool {
+        self.register(
+            Arc::as_ptr(node) as *const () as usize,
+            mem::size_of::<N>() + 2 * mem::size_of::<usize>(),
+        )
+    }
+
+    fn register(&mut self, ptr: usize, bytes: usize) -> bool {
+        if !self.seen.insert(ptr) {
+            return false;
+        }
+        self.versions.entry(ptr).or_insert((0, bytes)).0 += 1;
+        true
+    }
+
+    fn next_version(&mut self) {
+        self.seen.clear();
+    }
+
+    fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+        for (versions, bytes) in self.versions.values() {
+            stats.nodes += 1;
+            stats.heap_bytes += bytes;
+            if *versions > 1 {
+                stats.shared_nodes += 1;
+            }
+        }
+        stats
+    }
+}
+
+/// Measures the given versions of a collection.
+///
+/// Nodes are deduplicated by pointer, so a node reachable from several
+/// versions is only counted once in `nodes` and `heap_bytes`.
+///
+/// # Examples
+///
+/// ```
+/// use ds_13::unsync::List;
+///
+/// let v1 = List::cons(1, &List::cons(2, &List::new()));
+/// let v2 = v1.pushed_front(0);
+///
+/// let stats = ds_13::stats::measure(&[&v1, &v2]);
+/// assert_eq!(stats.nodes, 3);
+/// assert_eq!(stats.shared_nodes, 2);
+/// ```
+pub fn measure<M: Measure + ?Sized>(versions: &[&M]) -> Stats {
+    let mut visitor = NodeVisitor::new();
+    for version in versions {
+        visitor.next_version();
+        version.visit_nodes(&mut visitor);
+    }
+    visitor.stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unsync::list::List;
+    use crate::unsync::rb_map::RBMap;
+    use crate::unsync::rb_tree::RBTree;
+    use crate::unsync::tree::Tree;
+    use crate::unsynced_list;
+    use crate::sync::list::List as SyncedList;
+
+    #[test]
+    fn measure_empty_list() {
+        let list = List::<i32>::new();
+
+        assert_eq!(measure(&[&list]), Stats::default());
+    }
+
+    #[test]
+    fn measure_one_version() {
+        let list = unsynced_list!(1, 2, 3);
+
+        let stats = measure(&[&list]);
+
+        assert_eq!(stats.nodes, 3);
+        assert_eq!(stats.shared_nodes, 0);
+        assert!(stats.heap_bytes >= 3 * mem::size_of::<i32>());
+    }
+
+    #[test]
+    fn measure_shared_tail() {
+        let l1 = unsynced_list!(1, 2, 3);
+        let l2 = l1.pushed_front(4);
+        let l3 = l1.popped_front().pushed_front(5);
+
+        let stats = measure(&[&l1, &l2, &l3]);
+
+        assert_eq!(stats.nodes, 5);
+        assert_eq!(stats.shared_nodes, 3);
+        assert_eq!(stats.heap_bytes, 5 * measure(&[&List::from_value(0)]).heap_bytes);
+    }
+
+    #[test]
+    fn measure_same_version_twice_shares_everything() {
+        let l1 = unsynced_list!(1, 2);
+        let l2 = l1.clone();
+
+        let stats = measure(&[&l1, &l2]);
+
+        assert_eq!(stats.nodes, 2);
+        assert_eq!(stats.shared_nodes, 2);
+    }
+
+    #[test]
+    fn measure_deep_copy_shares_nothing() {
+        let l1 = unsynced_list!(1, 2);
+        let l2 = unsynced_list!(1, 2);
+
+        let stats = measure(&[&l1, &l2]);
+
+        assert_eq!(stats.nodes, 4);
+        assert_eq!(stats.shared_nodes, 0);
+    }
+
+    #[test]
+    fn measure_synced_list() {
+        let l1 = SyncedList::from_value(3).pushed_front(2).pushed_front(1);
+        let l2 = l1.pushed_front(4);
+
+        let stats = measure(&[&l1, &l2]);
+
+        assert_eq!(stats.nodes, 4);
+        assert_eq!(stats.shared_nodes, 3);
+    }
+
+    #[test]
+    fn measure_tree_counts_children() {
+        let leaf = Tree::leaf(2);
+        let t1 = Tree::tree(1, &unsynced_list!(leaf.clone(), leaf.clone()));
+        let t2 = Tree::tree(0, &unsynced_list!(leaf));
+
+        // t1: root, 2 list nodes, 1 shared leaf
+        assert_eq!(measure(&[&t1]).nodes, 4);
+        let stats = measure(&[&t1, &t2]);
+        assert_eq!(stats.nodes, 6);
+        assert_eq!(stats.shared_nodes, 1);
+    }
+
+    #[test]
+    fn measure_rb_tree_shares_untouched_subtrees() {
+        let mut t1 = RBTree::new();
+        for x in 0..16 {
+            t1 = t1.inserted(x);
+        }
+        let t2 = t1.inserted(16);
+
+        assert_eq!(measure(&[&t1]).nodes, 16);
+        let stats = measure(&[&t1, &t2]);
+        assert!(stats.shared_nodes > 0);
+        assert!(stats.nodes < 16 + 17);
+    }
+
+    #[test]
+    fn measure_rb_map() {
+        let m1 = RBMap::new().inserted(1, "a").inserted(2, "b");
+        let m2 = m1.inserted(3, "c");
+
+        assert_eq!(measure(&[&m1]).nodes, 2);
+        assert!(measure(&[&m1, &m2]).nodes >= 3);
+    }
+
+    #[test]
+    fn measure_mixed_collections() {
+        let list = unsynced_list!(1, 2);
+        let tree = Tree::leaf(1);
+
+        let stats = measure::<dyn Measure>(&[&list, &tree]);
+
+        assert_eq!(stats.nodes, 3);
+    }
+}
@@ -1,9 +1,15 @@
-use std::fmt::Debug;
-use std::fmt;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
 use itertools::{EitherOrBoth, Itertools};
+use crate::stats::{Measure, NodeVisitor};
+use crate::traits::{Filterable, Foldable, Functor, Monad};
+use crate::Error;
 
-#[derive(Debug)]
 pub struct List<T> {
     head: Link<T>,
 }
@@ -54,6 +60,14 @@ impl<T> List<T>
         self.head.is_none()
     }
 
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
     pub fn popped_front(&self) -> List<T> {
         if self.head.is_none() {
             panic!("You can't pop an empty list!");
@@ -61,14 +75,36 @@ impl<T> List<T>
         List { head: self.head.as_ref().and_then(|node| node.next.clone()) }
     }
 
+    /// Returns the tail of the list, or `Error::EmptyList` if the
+    /// list is empty.
+    pub fn try_popped_front(&self) -> Result<List<T>, Error> {
+        match &self.head {
+            None => Err(Error::EmptyList),
+            Some(node) => Ok(List { head: node.next.clone() }),
+        }
+    }
+
     pub fn tail(&self) -> List<T> {
         self.popped_front()
     }
 
+    pub fn try_tail(&self) -> Result<List<T>, Error> {
+        self.try_popped_front()
+    }
+
     pub fn head_tail(&self) -> (Option<&T>, List<T>) {
         (self.front(), self.tail())
     }
 
+    /// Returns the front element and the tail, or `Error::EmptyList`
+    /// if the list is empty.
+    pub fn try_head_tail(&self) -> Result<(&T, List<T>), Error> {
+        match &self.head {
+            None => Err(Error::EmptyList),
+            Some(node) => Ok((&node.element, List { head: node.next.clone() })),
+        }
+    }
+
     pub fn pushed_front(&self, value: T) -> List<T> {
         List::cons(value, self)
     }
@@ -84,6 +120,185 @@ impl<T> List<T>
          Iter { next: self.head.as_ref().map(|node| &**node) }
      }
 
+    /// Returns the number of elements.
+    ///
+    /// Complexity: O(n)
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns a reference to the element at index `n`, or `None` if
+    /// the list is too short.
+    ///
+    /// Complexity: O(n)
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    /// Returns a reference to the last element, or `None` if the
+    /// list is empty.
+    ///
+    /// Complexity: O(n)
+    pub fn last(&self) -> Option<&T> {
+        self.iter().last()
+    }
+
+    /// Returns `true` if the list contains an element equal to `x`.
+    ///
+    /// Complexity: O(n)
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|y| y == x)
+    }
+
+    /// Returns the list without its first `n` elements.
+    ///
+    /// The result shares all its nodes with `self`.
+    ///
+    /// Complexity: O(n)
+    pub fn drop(&self, n: usize) -> List<T> {
+        let mut link = &self.head;
+        for _ in 0..n {
+            match link {
+                None => break,
+                Some(node) => link = &node.next,
+            }
+        }
+        List { head: link.clone() }
+    }
+
+    /// Returns the first `n` elements.
+    ///
+    /// If the list has at most `n` elements, the result shares all
+    /// its nodes with `self`.
+    ///
+    /// Complexity: O(n)
+    pub fn take(&self, n: usize) -> List<T>
+    where
+        T: Clone,
+    {
+        self.split_at(n).0
+    }
+
+    /// Splits the list into its first `n` elements and the rest,
+    /// see `take` and `drop`.
+    ///
+    /// Complexity: O(n)
+    pub fn split_at(&self, n: usize) -> (List<T>, List<T>)
+    where
+        T: Clone,
+    {
+        let rest = self.drop(n);
+        if rest.is_empty() {
+            return (self.clone(), rest);
+        }
+        let front: Vec<&T> = self.iter().take(n).collect();
+        (prepended_all(front.into_iter().cloned(), List::empty()), rest)
+    }
+
+    /// Returns a list of pairs of the elements of `self` and `other`,
+    /// as long as the shorter of the two.
+    ///
+    /// Complexity: O(n)
+    pub fn zip<U>(&self, other: &List<U>) -> List<(T, U)>
+    where
+        T: Clone,
+        U: Clone,
+    {
+        let pairs: Vec<(T, U)> = self.iter().cloned().zip(other.iter().cloned()).collect();
+        prepended_all(pairs.into_iter(), List::empty())
+    }
+
+    /// Splits the list into the elements that satisfy `p` and the
+    /// elements that don't, keeping their order.
+    ///
+    /// The longest suffix where every element ends up in the same list
+    /// is shared with that list.
+    ///
+    /// Complexity: O(n)
+    pub fn partition(&self, mut p: impl FnMut(&T) -> bool) -> (List<T>, List<T>)
+    where
+        T: Clone,
+    {
+        let flags: Vec<bool> = self.iter().map(&mut p).collect();
+        let suffix_flag = match flags.last() {
+            None => return (List::empty(), List::empty()),
+            Some(flag) => *flag,
+        };
+        let split = flags.len() - flags.iter().rev().take_while(|flag| **flag == suffix_flag).count();
+        let (mut yes, mut no) = (Vec::new(), Vec::new());
+        for (x, flag) in self.iter().zip(&flags).take(split) {
+            if *flag {
+                yes.push(x);
+            } else {
+                no.push(x);
+            }
+        }
+        let (yes_tail, no_tail) = if suffix_flag {
+            (self.drop(split), List::empty())
+        } else {
+            (List::empty(), self.drop(split))
+        };
+        (
+            prepended_all(yes.into_iter().cloned(), yes_tail),
+            prepended_all(no.into_iter().cloned(), no_tail),
+        )
+    }
+
+    /// Removes consecutive repeated elements.
+    ///
+    /// The suffix after the last removed element is shared with `self`.
+    ///
+    /// Complexity: O(n)
+    pub fn dedup(&self) -> List<T>
+    where
+        T: Clone + PartialEq,
+    {
+        let elements: Vec<&T> = self.iter().collect();
+        match (1..elements.len()).rev().find(|&i| elements[i] == elements[i - 1]) {
+            None => self.clone(),
+            Some(last_removed) => {
+                let kept = elements[..last_removed]
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, x)| *i == 0 || elements[i - 1] != **x)
+                    .map(|(_i, x)| (*x).clone())
+                    .collect::<Vec<T>>();
+                prepended_all(kept.into_iter(), self.drop(last_removed + 1))
+            }
+        }
+    }
+
+    /// Sorts the list with a stable merge sort.
+    ///
+    /// The longest suffix that is already in its sorted position is
+    /// shared with `self`.
+    ///
+    /// Complexity: O(n log n)
+    pub fn sort_by(&self, mut compare: impl FnMut(&T, &T) -> Ordering) -> List<T>
+    where
+        T: Clone,
+    {
+        let elements: Vec<&T> = self.iter().collect();
+        let order = merge_sort(
+            (0..elements.len()).collect(),
+            &mut |i, j| compare(elements[i], elements[j])
+        );
+        let in_place = order
+            .iter()
+            .rev()
+            .zip((0..elements.len()).rev())
+            .take_while(|(i, j)| *i == j)
+            .count();
+        let split = elements.len() - in_place;
+        prepended_all(
+            order[..split].iter().map(|i| elements[*i].clone()),
+            self.drop(split)
+        )
+    }
+
 }
 
 #[macro_export]
@@ -99,6 +314,44 @@ macro_rules! synced_list {
     };
 }
 
+impl<A: Clone, B: Clone> List<(A, B)> {
+    /// Splits a list of pairs into a list of first and a list of
+    /// second elements.
+    ///
+    /// Complexity: O(n)
+    pub fn unzip(&self) -> (List<A>, List<B>) {
+        let (xs, ys): (Vec<A>, Vec<B>) = self.iter().cloned().unzip();
+        (prepended_all(xs.into_iter(), List::empty()), prepended_all(ys.into_iter(), List::empty()))
+    }
+}
+
+/// Stable merge sort of `indices` by `compare`.
+fn merge_sort(
+    mut indices: Vec<usize>,
+    compare: &mut impl FnMut(usize, usize) -> Ordering
+) -> Vec<usize> {
+    if indices.len() <= 1 {
+        return indices;
+    }
+    let right = indices.split_off(indices.len() / 2);
+    let left = merge_sort(indices, compare).into_iter();
+    let right = merge_sort(right, compare).into_iter();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut left, mut right) = (left.peekable(), right.peekable());
+    while let (Some(&i), Some(&j)) = (left.peek(), right.peek()) {
+        if compare(j, i) == Ordering::Less {
+            merged.push(j);
+            right.next();
+        } else {
+            merged.push(i);
+            left.next();
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
+
 impl<'a, T> IntoIterator for &'a List<T> 
 {
     type Item = &'a T;
@@ -132,7 +385,15 @@ impl<T> Clone for List<T>
     }
 }
 
-// impl<T: fmt::Debug> fmt::Debug for List<T> {}
+impl<T> fmt::Debug for List<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 impl<T> fmt::Display for List<T> 
 where
     T: fmt::Display,
@@ -159,32 +420,157 @@ impl<T> Drop for List<T> {
     }
 }
 
+impl<T> Measure for List<T> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        let mut link = &self.head;
+        while let Some(node) = link {
+            if !visitor.visit_arc(node) {
+                break;
+            }
+            link = &node.next;
+        }
+    }
+}
+
+impl<T> Foldable for List<T> {
+    type Item = T;
+
+    fn foldl<U>(&self, mut f: impl FnMut(U, &T) -> U, acc: U) -> U {
+        self.iter().fold(acc, &mut f)
+    }
+
+    fn foldr<U>(&self, mut f: impl FnMut(&T, U) -> U, acc: U) -> U {
+        let elements: Vec<&T> = self.iter().collect();
+        elements.into_iter().rev().fold(acc, |acc, x| f(x, acc))
+    }
+}
+
+impl<T> Functor for List<T> {
+    type Mapped<U> = List<U>;
+
+    fn fmap<U>(&self, mut f: impl FnMut(&T) -> U) -> List<U> {
+        let elements: Vec<U> = self.iter().map(&mut f).collect();
+        elements.into_iter().rev().fold(List::new(), |acc, x| acc.pushed_front(x))
+    }
+}
+
+impl<T: Clone> Filterable for List<T> {
+    fn filter(&self, p: impl FnMut(&T) -> bool) -> Self {
+        filter(p, self)
+    }
+}
+
+impl<T> Monad for List<T> {
+    fn mreturn(x: T) -> Self {
+        List::from_value(x)
+    }
+
+    fn and_then<U: Clone>(&self, f: impl FnMut(&T) -> List<U>) -> List<U> {
+        mbind(self, f)
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    /// Creates a list with the elements in iteration order.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let elements: Vec<T> = iter.into_iter().collect();
+        List::from(elements)
+    }
+}
+
+impl<T> From<Vec<T>> for List<T> {
+    /// Creates a list with the elements of the vector, the first
+    /// element in front.
+    fn from(elements: Vec<T>) -> Self {
+        prepended_all(elements.into_iter(), List::new())
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for List<T> {
+    fn from(elements: [T; N]) -> Self {
+        prepended_all(IntoIterator::into_iter(elements), List::new())
+    }
+}
+
 impl<T> PartialEq for List<T> 
 where
-    T: PartialEq + Clone,
+    T: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
         self.iter().zip_longest(other.iter()).all(|x| matches!(x, EitherOrBoth::Both(a, b) if a == b))
     }
 }
 
-pub fn filter<T: Copy>(
-    p: impl FnOnce(&T) -> bool + Copy, 
-    list: &List<T>
-) -> List<T> {
-    match list.front() {
-        Some(head) => {
-            let tail = filter(p, &list.popped_front());
-            if p(head) {
-                List::cons(*head, &tail)
-            } else {
-                tail
-            }
-        },
-        None => List::empty()
-        
+impl<T: Eq> Eq for List<T> {}
+
+impl<T> PartialOrd for List<T>
+where
+    T: PartialOrd,
+{
+    /// Compares the lists lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
     }
-} 
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for x in self {
+            x.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
+    }
+}
+
+/// Returns a list of the elements of `list` that satisfy `p`.
+///
+/// The longest suffix of `list` where every element satisfies `p`
+/// is shared with the result, only the kept elements before it are
+/// cloned.
+pub fn filter<T: Clone>(mut p: impl FnMut(&T) -> bool, list: &List<T>) -> List<T> {
+    let mut kept = Vec::new();
+    let mut run = Vec::new();
+    let mut suffix = &list.head;
+    let mut link = &list.head;
+    while let Some(node) = link {
+        if p(&node.element) {
+            run.push(&node.element);
+        } else {
+            kept.append(&mut run);
+            suffix = &node.next;
+        }
+        link = &node.next;
+    }
+    prepended_all(kept.into_iter().cloned(), List { head: suffix.clone() })
+}
+
+/// Conses the elements onto `tail`, keeping their order.
+fn prepended_all<T>(elements: impl DoubleEndedIterator<Item = T>, tail: List<T>) -> List<T> {
+    elements.rev().fold(tail, |acc, x| List::cons(x, &acc))
+}
+
+/// Returns `front` followed by `tail`, sharing `tail`.
+fn appended<T: Clone>(front: &List<T>, tail: List<T>) -> List<T> {
+    if tail.is_empty() {
+        return front.clone();
+    }
+    let elements: Vec<&T> = front.iter().collect();
+    prepended_all(elements.into_iter().cloned(), tail)
+}
 
 pub fn reverse<T: Clone>(list: &List<T>) -> List<T> {
     foldl(
@@ -267,9 +653,47 @@ pub fn mreturn<T>(t: T) -> List<T>
     List::cons(t, &List::empty())
 }
 
-pub fn mbind<A: Copy, B: Copy>(list: &List<A>, k: impl Fn(&A) -> List<B> + Copy) -> List<B> {
-    let list_list = fmap(k, list);
-    concat_all(&list_list)
+/// Maps every element to a list with `k` and concatenates the lists.
+///
+/// The list returned for the last element is shared with the result.
+pub fn mbind<A, B: Clone>(list: &List<A>, k: impl FnMut(&A) -> List<B>) -> List<B> {
+    let lists: Vec<List<B>> = list.iter().map(k).collect();
+    lists.into_iter().rev().fold(List::empty(), |acc, xs| appended(&xs, acc))
+}
+
+// List Applicative
+/// Applies every function in `fs` to every element in `xs`.
+pub fn ap<F, A, B>(fs: &List<F>, xs: &List<A>) -> List<B>
+where
+    F: Fn(&A) -> B,
+    B: Clone,
+{
+    mbind(fs, |f| xs.fmap(f))
+}
+
+/// Applies `f` to every pair of elements from `xs` and `ys`.
+pub fn lift2<A, B, C: Clone>(
+    mut f: impl FnMut(&A, &B) -> C,
+    xs: &List<A>,
+    ys: &List<B>
+) -> List<C> {
+    mbind(xs, |x| ys.fmap(|y| f(x, y)))
+}
+
+/// Returns every list that takes one element from each list in `xss`.
+///
+/// The returned lists share their tails.
+pub fn sequence<A: Clone>(xss: &List<List<A>>) -> List<List<A>> {
+    xss.foldr(
+        |xs, acc: List<List<A>>| mbind(xs, |x| acc.fmap(|ys| List::cons(x.clone(), ys))),
+        mreturn(List::empty())
+    )
+}
+
+/// Maps every element to a list of choices with `f` and returns
+/// every combination of choices, see `sequence`.
+pub fn traverse<A, B: Clone>(f: impl FnMut(&A) -> List<B>, xs: &List<A>) -> List<List<B>> {
+    sequence(&xs.fmap(f))
 }
 
 
@@ -317,6 +741,18 @@ mod tests {
         assert_eq!(l2.front(), Some(&6.7));
     }
 
+    #[test]
+    fn try_variants_return_error_on_empty_list() {
+        let empty = List::<i32>::empty();
+        let list = List::from_value(2).pushed_front(1);
+
+        assert_eq!(empty.try_popped_front(), Err(Error::EmptyList));
+        assert_eq!(empty.try_tail(), Err(Error::EmptyList));
+        assert_eq!(empty.try_head_tail(), Err(Error::EmptyList));
+        assert_eq!(list.try_tail(), Ok(List::from_value(2)));
+        assert_eq!(list.try_head_tail(), Ok((&1, List::from_value(2))));
+    }
+
     #[test]
     fn popped_front_returns_tail() {
         let l1 = List::empty();
@@ -400,4 +836,170 @@ mod tests {
 
         assert_eq!(list, synced_list!(3));
     }
+
+    #[test]
+    fn filter_works_without_copy() {
+        let list = synced_list!("c".to_string(), "bb".to_string(), "a".to_string());
+
+        let short = filter(|s: &String| s.len() == 1, &list);
+
+        assert_eq!(short, synced_list!("c".to_string(), "a".to_string()));
+    }
+
+    #[test]
+    fn mbind_concatenates_in_order() {
+        let list = synced_list!(2, 1);
+
+        let result = mbind(&list, |x| synced_list!(x.to_string(), "-".repeat(*x)));
+
+        assert_eq!(
+            result,
+            synced_list!(
+                "2".to_string(), "--".to_string(), "1".to_string(), "-".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn lift2_combines_every_pair() {
+        let xs = synced_list!("b", "a");
+        let ys = synced_list!(2, 1);
+
+        let result = lift2(|x, y| format!("{}{}", x, y), &xs, &ys);
+
+        assert_eq!(
+            result,
+            synced_list!(
+                "b2".to_string(), "b1".to_string(), "a2".to_string(), "a1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn traverse_maps_and_sequences() {
+        let xs = synced_list!(2, 1);
+
+        let result = traverse(|x| synced_list!(-x, *x), &xs);
+
+        assert_eq!(result.iter().count(), 4);
+        assert_eq!(result.front(), Some(&synced_list!(2, 1)));
+    }
+
+    #[test]
+    fn len_nth_last_and_contains() {
+        let list = synced_list!(3, 2, 1);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(List::<i32>::empty().len(), 0);
+        assert_eq!(list.nth(0), Some(&1));
+        assert_eq!(list.nth(2), Some(&3));
+        assert_eq!(list.nth(3), None);
+        assert_eq!(list.last(), Some(&3));
+        assert_eq!(List::<i32>::empty().last(), None);
+        assert!(list.contains(&2));
+        assert!(!list.contains(&4));
+    }
+
+    #[test]
+    fn drop_returns_shared_suffix() {
+        let list = synced_list!(3, 2, 1);
+
+        assert!(list.drop(0).ptr_eq(&list));
+        assert!(list.drop(1).ptr_eq(&list.popped_front()));
+        assert_eq!(list.drop(2), synced_list!(3));
+        assert!(list.drop(3).is_empty());
+        assert!(list.drop(4).is_empty());
+    }
+
+    #[test]
+    fn take_and_split_at() {
+        let list = synced_list!(3, 2, 1);
+
+        assert_eq!(list.take(2), synced_list!(2, 1));
+        assert!(list.take(3).ptr_eq(&list));
+        assert!(list.take(0).is_empty());
+
+        let (front, rest) = list.split_at(1);
+        assert_eq!(front, synced_list!(1));
+        assert!(rest.ptr_eq(&list.popped_front()));
+    }
+
+    #[test]
+    fn zip_and_unzip() {
+        let xs = synced_list!(3, 2, 1);
+        let ys = synced_list!("b", "a");
+
+        let pairs = xs.zip(&ys);
+        assert_eq!(pairs, synced_list!((2, "b"), (1, "a")));
+
+        let (xs, ys) = pairs.unzip();
+        assert_eq!(xs, synced_list!(2, 1));
+        assert_eq!(ys, synced_list!("b", "a"));
+    }
+
+    #[test]
+    fn partition_keeps_order_and_shares_suffix() {
+        let list = synced_list!(6, 4, 3, 2, 1);
+
+        let (evens, odds) = list.partition(|x| x % 2 == 0);
+
+        assert_eq!(evens, synced_list!(6, 4, 2));
+        assert_eq!(odds, synced_list!(3, 1));
+        assert!(evens.drop(1).ptr_eq(&list.drop(3)));
+
+        let (all, none) = list.partition(|_| true);
+        assert!(all.ptr_eq(&list));
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn dedup_removes_consecutive_duplicates() {
+        let list = synced_list!(3, 2, 1, 1, 2, 2);
+
+        let deduped = list.dedup();
+
+        assert_eq!(deduped, synced_list!(3, 2, 1, 2));
+        assert!(deduped.drop(2).ptr_eq(&list.drop(4)));
+        let unique = synced_list!(1, 2, 1);
+        assert!(unique.dedup().ptr_eq(&unique));
+    }
+
+    #[test]
+    fn sort_by_is_stable() {
+        let list = synced_list!((1, "c"), (0, "b"), (1, "a"), (0, "z"));
+
+        let sorted = list.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(sorted, synced_list!((1, "c"), (1, "a"), (0, "b"), (0, "z")));
+    }
+
+    #[test]
+    fn sort_by_shares_sorted_suffix() {
+        let sorted = synced_list!(5, 4, 3, 2, 1);
+        assert!(sorted.sort_by(|a, b| a.cmp(b)).ptr_eq(&sorted));
+
+        let list = synced_list!(5, 4, 1, 3, 2);
+        let result = list.sort_by(|a, b| a.cmp(b));
+        assert_eq!(result, sorted);
+        assert!(result.drop(3).ptr_eq(&list.drop(3)));
+        assert!(List::<i32>::empty().sort_by(|a, b| a.cmp(b)).is_empty());
+    }
+
+    #[test]
+    fn standard_traits() {
+        use std::collections::HashSet;
+
+        let list = List::from(vec!(1, 2, 3));
+
+        assert_eq!(list, List::from([1, 2, 3]));
+        assert_eq!(list, (1..4).collect::<List<_>>());
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert!(list < List::from([1, 3]));
+        assert!(List::<i32>::default().is_empty());
+
+        let keys: HashSet<List<i32>> = vec!(list.clone(), list.popped_front().pushed_front(1))
+            .into_iter()
+            .collect();
+        assert_eq!(keys.len(), 1);
+    }
 }
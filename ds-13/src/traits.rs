@@ -0,0 +1,195 @@
+/// A collection that can be folded into a single value.
+///
+/// `List` folds from front to back, `Tree` in pre-order and `RBTree`
+/// and `RBMap` (over its values) in ascending order.
+pub trait Foldable {
+    type Item;
+
+    /// Folds the elements from the first to the last.
+    fn foldl<U>(&self, f: impl FnMut(U, &Self::Item) -> U, acc: U) -> U;
+
+    /// Folds the elements from the last to the first.
+    fn foldr<U>(&self, f: impl FnMut(&Self::Item, U) -> U, acc: U) -> U;
+
+    /// Returns the number of elements.
+    fn length(&self) -> usize {
+        self.foldl(|n, _x| n + 1, 0)
+    }
+}
+
+/// A collection that can be mapped into a collection of the same shape.
+///
+/// `RBTree` is not a `Functor`, since mapping its elements can break
+/// the ordering. `RBMap` maps its values and keeps its keys.
+pub trait Functor: Foldable {
+    type Mapped<U>;
+
+    fn fmap<U>(&self, f: impl FnMut(&Self::Item) -> U) -> Self::Mapped<U>;
+}
+
+/// A collection that can be filtered into a collection of the same type.
+pub trait Filterable: Foldable + Sized {
+    fn filter(&self, p: impl FnMut(&Self::Item) -> bool) -> Self;
+}
+
+/// A `Functor` with `mreturn` and a monadic bind, `and_then`.
+///
+/// For `List` this is the nondeterminism monad, for `Tree` the
+/// rose tree monad, where the children of every subtree are appended
+/// to the children of the tree its root is mapped to. A subtree whose
+/// root is mapped to an empty tree is dropped, children included.
+pub trait Monad: Functor {
+    fn mreturn(x: Self::Item) -> Self;
+
+    fn and_then<U: Clone>(&self, f: impl FnMut(&Self::Item) -> Self::Mapped<U>) -> Self::Mapped<U>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync;
+    use crate::unsync::list::List;
+    use crate::unsync::rb_map::RBMap;
+    use crate::unsync::rb_tree::RBTree;
+    use crate::unsync::tree::Tree;
+    use crate::unsynced_list;
+
+    fn sum<F: Foldable<Item = i32>>(xs: &F) -> i32 {
+        xs.foldl(|acc, x| acc + x, 0)
+    }
+
+    fn to_vec<F: Foldable>(xs: &F) -> Vec<F::Item>
+    where
+        F::Item: Clone,
+    {
+        xs.foldr(|x, mut acc: Vec<F::Item>| { acc.insert(0, x.clone()); acc }, Vec::new())
+    }
+
+    fn rb_tree(xs: &[i32]) -> RBTree<i32> {
+        xs.iter().fold(RBTree::new(), |t, x| t.inserted(*x))
+    }
+
+    fn tree() -> Tree<i32> {
+        Tree::tree(
+            1,
+            &unsynced_list!(
+                Tree::leaf(4),
+                Tree::tree(2, &unsynced_list!(Tree::leaf(3)))
+            )
+        )
+    }
+
+    #[test]
+    fn generic_fold_over_all_collections() {
+        let map = RBMap::new().inserted("a", 1).inserted("b", 2);
+
+        assert_eq!(sum(&unsynced_list!(3, 2, 1)), 6);
+        assert_eq!(sum(&sync::list::List::from_value(6)), 6);
+        assert_eq!(sum(&rb_tree(&[2, 1, 3])), 6);
+        assert_eq!(sum(&map), 3);
+        assert_eq!(sum(&tree()), 10);
+    }
+
+    #[test]
+    fn foldl_and_foldr_keep_order() {
+        assert_eq!(to_vec(&unsynced_list!(3, 2, 1)), vec!(1, 2, 3));
+        assert_eq!(to_vec(&rb_tree(&[3, 1, 2])), vec!(1, 2, 3));
+        assert_eq!(to_vec(&tree()), vec!(1, 2, 3, 4));
+        assert_eq!(
+            tree().foldl(|mut acc: Vec<i32>, x| { acc.push(*x); acc }, Vec::new()),
+            vec!(1, 2, 3, 4)
+        );
+    }
+
+    #[test]
+    fn length_counts_elements() {
+        assert_eq!(List::<i32>::new().length(), 0);
+        assert_eq!(unsynced_list!(1, 2).length(), 2);
+        assert_eq!(rb_tree(&[1, 1, 2]).length(), 2);
+        assert_eq!(Tree::<i32>::new().length(), 0);
+        assert_eq!(tree().length(), 4);
+    }
+
+    #[test]
+    fn fmap_keeps_shape() {
+        let list = unsynced_list!(3, 2, 1).fmap(|x| x * 10);
+        assert_eq!(list, unsynced_list!(30, 20, 10));
+
+        let mapped = tree().fmap(|x| x.to_string());
+        assert_eq!(mapped.root(), Some(&"1".to_string()));
+        assert_eq!(to_vec(&mapped), vec!("1", "2", "3", "4"));
+
+        let map = RBMap::new().inserted(1, "a").inserted(2, "bb").fmap(|v| v.len());
+        assert_eq!(map.get(&2), Some(&2));
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec!((&1, &1), (&2, &2)));
+    }
+
+    #[test]
+    fn filter_keeps_matching_elements() {
+        let even = |x: &i32| x % 2 == 0;
+
+        assert_eq!(Filterable::filter(&unsynced_list!(4, 3, 2, 1), even), unsynced_list!(4, 2));
+        assert_eq!(to_vec(&Filterable::filter(&rb_tree(&[1, 2, 3, 4]), even)), vec!(2, 4));
+
+        let map = RBMap::new().inserted("a", 1).inserted("b", 2);
+        let map = Filterable::filter(&map, even);
+        assert!(!map.contains_key(&"a"));
+        assert!(map.contains_key(&"b"));
+    }
+
+    #[test]
+    fn list_and_then_is_order_preserving_concat_map() {
+        let list = unsynced_list!(2, 1);
+
+        let result = list.and_then(|x| unsynced_list!(*x * 10, *x));
+
+        assert_eq!(to_vec(&result), vec!(1, 10, 2, 20));
+        assert_eq!(List::mreturn(5), unsynced_list!(5));
+    }
+
+    #[test]
+    fn synced_list_and_then() {
+        let list = sync::list::List::from_value(2).pushed_front(1);
+
+        let result = list.and_then(|x| sync::list::List::mreturn(*x + 1));
+
+        assert_eq!(to_vec(&result), vec!(2, 3));
+    }
+
+    #[test]
+    fn tree_and_then_appends_children() {
+        let t = Tree::tree(1, &unsynced_list!(Tree::leaf(2)));
+
+        let result = t.and_then(|x| Tree::tree(*x, &unsynced_list!(Tree::leaf(-x))));
+
+        // 1[-1 2[-2]]
+        assert_eq!(result.root(), Some(&1));
+        assert_eq!(to_vec(&result), vec!(1, -1, 2, -2));
+        assert_eq!(Tree::mreturn(3), Tree::leaf(3));
+    }
+
+    #[test]
+    fn tree_and_then_drops_subtrees_mapped_to_empty() {
+        let t = Tree::tree(1, &unsynced_list!(Tree::tree(2, &unsynced_list!(Tree::leaf(3))), Tree::leaf(4)));
+
+        let result = t.and_then(|x| if *x == 2 { Tree::new() } else { Tree::leaf(*x) });
+
+        assert_eq!(to_vec(&result), vec!(1, 4));
+        assert_eq!(result.children().len(), 1);
+        assert!(Tree::leaf(1).and_then(|_| Tree::<i32>::new()).is_empty());
+    }
+
+    #[test]
+    fn monad_laws_hold_for_list() {
+        let k = |x: &i32| unsynced_list!(*x, *x + 1);
+        let h = |x: &i32| unsynced_list!(*x * 2);
+        let m = unsynced_list!(3, 2, 1);
+
+        assert_eq!(List::mreturn(4).and_then(k), k(&4));
+        assert_eq!(m.and_then(|x| List::mreturn(*x)), m);
+        assert_eq!(
+            m.and_then(k).and_then(h),
+            m.and_then(|x| k(x).and_then(h))
+        );
+    }
+}
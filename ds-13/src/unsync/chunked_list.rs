@@ -0,0 +1,692 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use crate::stats::{Measure, NodeVisitor};
+use crate::traits::{Filterable, Foldable, Functor, Monad};
+use crate::Error;
+
+const CHUNK_SIZE: usize = 16;
+
+/// An unrolled persistent list, that stores up to 16 elements per node.
+///
+/// It has the same API as `List`, but iteration touches one node per
+/// 16 elements and every element doesn't need its own `Rc`.
+///
+/// A chunk is filled from its first slot, and a list refers to a chunk
+/// together with the number of its slots that belong to the list.
+/// `cons` fills the next free slot of the tail's chunk when nobody
+/// has filled it before, so building a list by repeated `cons` fills
+/// whole chunks. Otherwise, e.g. when consing twice onto the same
+/// tail, it starts a new chunk, so `cons` never copies any elements.
+///
+/// An element stays in its chunk until the chunk is dropped, even if
+/// the list it was consed onto is dropped.
+pub struct ChunkedList<T> {
+    head: Link<T>,
+}
+
+/// A chunk and the number of its slots that belong to the list (at least 1).
+type Link<T> = Option<(Rc<Chunk<T>>, usize)>;
+
+#[derive(Debug)]
+struct Chunk<T> {
+    elements: [OnceCell<T>; CHUNK_SIZE],
+    next: Link<T>,
+}
+
+impl<T> ChunkedList<T> {
+    /// Creates an empty `ChunkedList`.
+    ///
+    /// # Examples
+    /// ```
+    /// use ds_13::unsync::chunked_list::ChunkedList;
+    /// let list: ChunkedList<u32> = ChunkedList::new();
+    /// ```
+    pub fn new() -> ChunkedList<T> {
+        ChunkedList { head: None }
+    }
+
+    /// Creates a list with the element given as head
+    /// and the provided list as tail.
+    ///
+    /// Complexity: O(1)
+    ///
+    /// # Examples
+    /// ```
+    /// use ds_13::unsync::chunked_list::ChunkedList;
+    ///
+    /// let tail = ChunkedList::cons(1, &ChunkedList::new());
+    /// let l1 = ChunkedList::cons(2, &tail);
+    /// let l2 = ChunkedList::cons(3, &tail);
+    ///
+    /// assert_eq!(l1.iter().collect::<Vec<_>>(), vec!(&2, &1));
+    /// assert_eq!(l2.iter().collect::<Vec<_>>(), vec!(&3, &1));
+    /// ```
+    pub fn cons(element: T, tail: &ChunkedList<T>) -> ChunkedList<T> {
+        let element = match &tail.head {
+            Some((chunk, len)) if *len < CHUNK_SIZE => match chunk.elements[*len].set(element) {
+                Ok(()) => return ChunkedList { head: Some((Rc::clone(chunk), len + 1)) },
+                Err(element) => element,
+            },
+            _ => element,
+        };
+        let chunk = Chunk {
+            elements: core::array::from_fn(|_| OnceCell::new()),
+            next: tail.head.clone(),
+        };
+        let _ = chunk.elements[0].set(element);
+        ChunkedList { head: Some((Rc::new(chunk), 1)) }
+    }
+
+    pub fn from_value(element: T) -> ChunkedList<T> {
+        ChunkedList::cons(element, &ChunkedList::new())
+    }
+
+    /// Provides a reference to the front element, or
+    /// `None` if the list is empty.
+    pub fn front(&self) -> Option<&T> {
+        self.head.as_ref().and_then(|(chunk, len)| chunk.elements[len - 1].get())
+    }
+
+    /// Returns `true` if this `ChunkedList` is empty.
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns `true` if both lists share the same front element.
+    ///
+    /// Complexity: O(1)
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (None, None) => true,
+            (Some((c1, l1)), Some((c2, l2))) => Rc::ptr_eq(c1, c2) && l1 == l2,
+            _ => false,
+        }
+    }
+
+    /// Returns the tail of the list.
+    ///
+    /// # Panics
+    ///
+    /// This method panics when called on an empty list.
+    pub fn popped_front(&self) -> ChunkedList<T> {
+        match self.try_popped_front() {
+            Err(_) => panic!("You can't pop an empty list!"),
+            Ok(tail) => tail,
+        }
+    }
+
+    /// Returns the tail of the list, or `Error::EmptyList` if the
+    /// list is empty.
+    pub fn try_popped_front(&self) -> Result<ChunkedList<T>, Error> {
+        match &self.head {
+            None => Err(Error::EmptyList),
+            Some((chunk, 1)) => Ok(ChunkedList { head: chunk.next.clone() }),
+            Some((chunk, len)) => Ok(ChunkedList { head: Some((Rc::clone(chunk), len - 1)) }),
+        }
+    }
+
+    pub fn tail(&self) -> ChunkedList<T> {
+        self.popped_front()
+    }
+
+    pub fn try_tail(&self) -> Result<ChunkedList<T>, Error> {
+        self.try_popped_front()
+    }
+
+    pub fn head_tail(&self) -> (Option<&T>, ChunkedList<T>) {
+        (self.front(), self.tail())
+    }
+
+    /// Returns the front element and the tail, or `Error::EmptyList`
+    /// if the list is empty.
+    pub fn try_head_tail(&self) -> Result<(&T, ChunkedList<T>), Error> {
+        let tail = self.try_popped_front()?;
+        self.front().map(|x| (x, tail)).ok_or(Error::EmptyList)
+    }
+
+    pub fn pushed_front(&self, value: T) -> ChunkedList<T> {
+        ChunkedList::cons(value, self)
+    }
+
+    pub fn reversed(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.iter().fold(ChunkedList::new(), |acc, x| acc.pushed_front(x.clone()))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: self.head.as_ref().map(|(chunk, len)| (&**chunk, *len)) }
+    }
+
+    /// Returns the number of elements.
+    ///
+    /// Complexity: O(n / 16)
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        let mut link = &self.head;
+        while let Some((chunk, n)) = link {
+            len += n;
+            link = &chunk.next;
+        }
+        len
+    }
+
+    /// Returns a reference to the element at index `n`, or `None` if
+    /// the list is too short.
+    ///
+    /// Complexity: O(n / 16)
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        let mut n = n;
+        let mut link = &self.head;
+        while let Some((chunk, len)) = link {
+            if n < *len {
+                return chunk.elements[len - 1 - n].get();
+            }
+            n -= len;
+            link = &chunk.next;
+        }
+        None
+    }
+
+    /// Returns a reference to the last element, or `None` if the
+    /// list is empty.
+    ///
+    /// Complexity: O(n / 16)
+    pub fn last(&self) -> Option<&T> {
+        let mut chunk = &self.head.as_ref()?.0;
+        while let Some((next, _len)) = &chunk.next {
+            chunk = next;
+        }
+        chunk.elements[0].get()
+    }
+
+    /// Returns `true` if the list contains an element equal to `x`.
+    ///
+    /// Complexity: O(n)
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|y| y == x)
+    }
+
+    /// Returns the list without its first `n` elements.
+    ///
+    /// The result shares all its chunks with `self`.
+    ///
+    /// Complexity: O(n / 16)
+    pub fn drop(&self, n: usize) -> ChunkedList<T> {
+        let mut n = n;
+        let mut link = &self.head;
+        while let Some((chunk, len)) = link {
+            if n < *len {
+                return ChunkedList { head: Some((Rc::clone(chunk), len - n)) };
+            }
+            n -= len;
+            link = &chunk.next;
+        }
+        ChunkedList::new()
+    }
+
+    /// Returns the first `n` elements.
+    ///
+    /// Complexity: O(n)
+    pub fn take(&self, n: usize) -> ChunkedList<T>
+    where
+        T: Clone,
+    {
+        self.split_at(n).0
+    }
+
+    /// Splits the list into its first `n` elements and the rest,
+    /// see `take` and `drop`.
+    ///
+    /// Complexity: O(n)
+    pub fn split_at(&self, n: usize) -> (ChunkedList<T>, ChunkedList<T>)
+    where
+        T: Clone,
+    {
+        let rest = self.drop(n);
+        if rest.is_empty() {
+            return (self.clone(), rest);
+        }
+        (prepended_all(self.iter().take(n).cloned(), ChunkedList::new()), rest)
+    }
+
+    /// Returns a list of pairs of the elements of `self` and `other`,
+    /// as long as the shorter of the two.
+    ///
+    /// Complexity: O(n)
+    pub fn zip<U>(&self, other: &ChunkedList<U>) -> ChunkedList<(T, U)>
+    where
+        T: Clone,
+        U: Clone,
+    {
+        prepended_all(self.iter().cloned().zip(other.iter().cloned()), ChunkedList::new())
+    }
+
+    /// Splits the list into the elements that satisfy `p` and the
+    /// elements that don't, keeping their order.
+    ///
+    /// Complexity: O(n)
+    pub fn partition(&self, mut p: impl FnMut(&T) -> bool) -> (ChunkedList<T>, ChunkedList<T>)
+    where
+        T: Clone,
+    {
+        let (yes, no): (Vec<&T>, Vec<&T>) = self.iter().partition(|x| p(x));
+        (
+            prepended_all(yes.into_iter().cloned(), ChunkedList::new()),
+            prepended_all(no.into_iter().cloned(), ChunkedList::new()),
+        )
+    }
+
+    /// Removes consecutive repeated elements.
+    ///
+    /// Complexity: O(n)
+    pub fn dedup(&self) -> ChunkedList<T>
+    where
+        T: Clone + PartialEq,
+    {
+        let mut elements: Vec<&T> = self.iter().collect();
+        let len = elements.len();
+        elements.dedup();
+        if elements.len() == len {
+            return self.clone();
+        }
+        prepended_all(elements.into_iter().cloned(), ChunkedList::new())
+    }
+
+    /// Sorts the list with a stable sort.
+    ///
+    /// Complexity: O(n log n)
+    pub fn sort_by(&self, mut compare: impl FnMut(&T, &T) -> Ordering) -> ChunkedList<T>
+    where
+        T: Clone,
+    {
+        let mut elements: Vec<&T> = self.iter().collect();
+        elements.sort_by(|a, b| compare(a, b));
+        prepended_all(elements.into_iter().cloned(), ChunkedList::new())
+    }
+}
+
+#[macro_export]
+macro_rules! chunked_list {
+    ( $( $x:expr ),* ) => {
+        {
+            let mut temp_list = ChunkedList::new();
+            $(
+                temp_list = temp_list.pushed_front($x);
+             )*
+            temp_list
+        }
+    };
+}
+
+impl<A: Clone, B: Clone> ChunkedList<(A, B)> {
+    /// Splits a list of pairs into a list of first and a list of
+    /// second elements.
+    ///
+    /// Complexity: O(n)
+    pub fn unzip(&self) -> (ChunkedList<A>, ChunkedList<B>) {
+        let (xs, ys): (Vec<A>, Vec<B>) = self.iter().cloned().unzip();
+        (
+            prepended_all(xs.into_iter(), ChunkedList::new()),
+            prepended_all(ys.into_iter(), ChunkedList::new()),
+        )
+    }
+}
+
+/// Returns `tail` with `elements` in front of it, in the same order.
+fn prepended_all<T>(
+    elements: impl Iterator<Item = T>,
+    tail: ChunkedList<T>
+) -> ChunkedList<T> {
+    let elements: Vec<T> = elements.collect();
+    elements.into_iter().rev().fold(tail, |acc, x| acc.pushed_front(x))
+}
+
+impl<T> Default for ChunkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for ChunkedList<T> {
+    /// Creates a list with the elements in iteration order.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        prepended_all(iter.into_iter(), ChunkedList::new())
+    }
+}
+
+impl<T> From<Vec<T>> for ChunkedList<T> {
+    fn from(elements: Vec<T>) -> Self {
+        prepended_all(elements.into_iter(), ChunkedList::new())
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for ChunkedList<T> {
+    fn from(elements: [T; N]) -> Self {
+        prepended_all(IntoIterator::into_iter(elements), ChunkedList::new())
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ChunkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<(&'a Chunk<T>, usize)>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (chunk, len) = self.next?;
+        self.next = if len > 1 {
+            Some((chunk, len - 1))
+        } else {
+            chunk.next.as_ref().map(|(next, len)| (&**next, *len))
+        };
+        chunk.elements[len - 1].get()
+    }
+}
+
+impl<T> Clone for ChunkedList<T> {
+    fn clone(&self) -> Self {
+        ChunkedList { head: self.head.clone() }
+    }
+}
+
+impl<T> fmt::Debug for ChunkedList<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> fmt::Display for ChunkedList<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "list [")?;
+        for x in self {
+            write!(f, "{}", x)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<T> Drop for ChunkedList<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        while let Some((chunk, _len)) = head {
+            if let Ok(mut chunk) = Rc::try_unwrap(chunk) {
+                head = chunk.next.take();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+impl<T> Measure for ChunkedList<T> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        let mut link = &self.head;
+        while let Some((chunk, _len)) = link {
+            if !visitor.visit(chunk) {
+                break;
+            }
+            link = &chunk.next;
+        }
+    }
+}
+
+impl<T> Foldable for ChunkedList<T> {
+    type Item = T;
+
+    fn foldl<U>(&self, mut f: impl FnMut(U, &T) -> U, acc: U) -> U {
+        self.iter().fold(acc, &mut f)
+    }
+
+    fn foldr<U>(&self, mut f: impl FnMut(&T, U) -> U, acc: U) -> U {
+        let elements: Vec<&T> = self.iter().collect();
+        elements.into_iter().rev().fold(acc, |acc, x| f(x, acc))
+    }
+
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Functor for ChunkedList<T> {
+    type Mapped<U> = ChunkedList<U>;
+
+    fn fmap<U>(&self, f: impl FnMut(&T) -> U) -> ChunkedList<U> {
+        prepended_all(self.iter().map(f), ChunkedList::new())
+    }
+}
+
+impl<T: Clone> Filterable for ChunkedList<T> {
+    fn filter(&self, mut p: impl FnMut(&T) -> bool) -> Self {
+        prepended_all(self.iter().filter(|x| p(x)).cloned(), ChunkedList::new())
+    }
+}
+
+impl<T> Monad for ChunkedList<T> {
+    fn mreturn(x: T) -> Self {
+        ChunkedList::from_value(x)
+    }
+
+    fn and_then<U: Clone>(&self, f: impl FnMut(&T) -> ChunkedList<U>) -> ChunkedList<U> {
+        let lists: Vec<ChunkedList<U>> = self.iter().map(f).collect();
+        let mut lists = lists.into_iter().rev();
+        let last = lists.next().unwrap_or_default();
+        lists.fold(last, |acc, list| prepended_all(list.iter().cloned(), acc))
+    }
+}
+
+impl<T> PartialEq for ChunkedList<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ChunkedList<T> {}
+
+impl<T> PartialOrd for ChunkedList<T>
+where
+    T: PartialOrd,
+{
+    /// Compares the lists lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for ChunkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for ChunkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for x in self {
+            x.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::measure;
+
+    fn to_vec<T: Clone>(list: &ChunkedList<T>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+
+    #[test]
+    fn new_creates_empty_list() {
+        let list = ChunkedList::<i32>::new();
+
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn cons_fills_chunks() {
+        let list: ChunkedList<usize> = (0..40).collect();
+
+        assert_eq!(list.len(), 40);
+        assert_eq!(to_vec(&list), (0..40).collect::<Vec<_>>());
+        assert_eq!(measure(&[&list]).nodes, 3);
+    }
+
+    #[test]
+    fn cons_on_same_tail_starts_new_chunk() {
+        let tail = chunked_list!(2, 1);
+        let l1 = tail.pushed_front(3);
+        let l2 = tail.pushed_front(4);
+
+        assert_eq!(to_vec(&l1), vec!(3, 1, 2));
+        assert_eq!(to_vec(&l2), vec!(4, 1, 2));
+        assert_eq!(to_vec(&tail), vec!(1, 2));
+        assert_eq!(measure(&[&l1, &l2]).nodes, 2);
+        assert!(l2.popped_front().ptr_eq(&tail));
+    }
+
+    #[test]
+    fn popped_front_shares_chunk() {
+        let list = chunked_list!(3, 2, 1);
+
+        let tail = list.popped_front();
+
+        assert_eq!(to_vec(&tail), vec!(2, 3));
+        assert_eq!(measure(&[&list, &tail]).shared_nodes, 1);
+        assert!(list.popped_front().popped_front().popped_front().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn popped_front_on_empty_list_panics() {
+        ChunkedList::<i32>::new().popped_front();
+    }
+
+    #[test]
+    fn try_variants_return_error_on_empty_list() {
+        let list = chunked_list!(2, 1);
+
+        assert!(ChunkedList::<i32>::new().try_popped_front().is_err());
+        assert!(ChunkedList::<i32>::new().try_head_tail().is_err());
+        assert_eq!(to_vec(&list.try_tail().unwrap()), vec!(2));
+        assert_eq!(list.try_head_tail().map(|(x, _tail)| *x), Ok(1));
+    }
+
+    #[test]
+    fn cons_after_pop_keeps_original() {
+        let list = chunked_list!(2, 1);
+        let other = list.popped_front().pushed_front(5);
+
+        assert_eq!(to_vec(&list), vec!(1, 2));
+        assert_eq!(to_vec(&other), vec!(5, 2));
+    }
+
+    #[test]
+    fn drop_and_nth_cross_chunks() {
+        let list: ChunkedList<usize> = (0..50).collect();
+
+        assert_eq!(list.nth(0), Some(&0));
+        assert_eq!(list.nth(17), Some(&17));
+        assert_eq!(list.nth(50), None);
+        assert_eq!(list.last(), Some(&49));
+        assert_eq!(to_vec(&list.drop(35)), (35..50).collect::<Vec<_>>());
+        assert!(list.drop(60).is_empty());
+    }
+
+    #[test]
+    fn split_at_take_and_zip() {
+        let list = chunked_list!(4, 3, 2, 1);
+
+        let (front, rest) = list.split_at(1);
+
+        assert_eq!(to_vec(&front), vec!(1));
+        assert_eq!(to_vec(&rest), vec!(2, 3, 4));
+        assert_eq!(list.take(10), list);
+        assert_eq!(to_vec(&list.zip(&chunked_list!('b', 'a'))), vec!((1, 'a'), (2, 'b')));
+        assert_eq!(list.zip(&chunked_list!('b', 'a')).unzip().1, chunked_list!('b', 'a'));
+    }
+
+    #[test]
+    fn partition_dedup_and_sort() {
+        let list = chunked_list!(3, 1, 1, 2);
+
+        let (even, odd) = list.partition(|x| x % 2 == 0);
+
+        assert_eq!(to_vec(&even), vec!(2));
+        assert_eq!(to_vec(&odd), vec!(1, 1, 3));
+        assert_eq!(to_vec(&list.dedup()), vec!(2, 1, 3));
+        assert_eq!(to_vec(&list.sort_by(|a, b| a.cmp(b))), vec!(1, 1, 2, 3));
+        assert_eq!(to_vec(&list.reversed()), vec!(3, 1, 1, 2));
+    }
+
+    #[test]
+    fn traits_match_list() {
+        let list = chunked_list!(3, 2, 1);
+
+        assert_eq!(list.foldl(|acc, x| acc + x, 0), 6);
+        assert_eq!(list.length(), 3);
+        assert_eq!(to_vec(&list.fmap(|x| x * 10)), vec!(10, 20, 30));
+        assert_eq!(to_vec(&Filterable::filter(&list, |x| *x != 2)), vec!(1, 3));
+        assert_eq!(to_vec(&list.and_then(|x| chunked_list!(*x, -x))), vec!(-1, 1, -2, 2, -3, 3));
+        assert_eq!(format!("{}", list), "list [123]");
+    }
+
+    #[test]
+    fn drop_long_list() {
+        let list: ChunkedList<usize> = (0..1_000_000).collect();
+
+        assert_eq!(list.len(), 1_000_000);
+    }
+
+    #[test]
+    fn standard_traits() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |list: &ChunkedList<i32>| {
+            let mut hasher = DefaultHasher::new();
+            list.hash(&mut hasher);
+            hasher.finish()
+        };
+        let list = ChunkedList::from(vec!(1, 2, 3));
+
+        assert_eq!(list, ChunkedList::from([1, 2, 3]));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert!(list < ChunkedList::from([1, 3]));
+        assert!(list > ChunkedList::from([1, 2]));
+
+        assert_eq!(hash(&list), hash(&(1..4).collect()));
+    }
+}
@@ -0,0 +1,476 @@
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use crate::unsync::rb_map::RBMap;
+use crate::unsync::rb_tree::{self, RBSet};
+use crate::stats::{Measure, NodeVisitor};
+
+/// A persistent directed graph.
+///
+/// Every node is a key of the successor map, also nodes without
+/// edges. The predecessors are kept as well, so that `without_node`
+/// doesn't have to look at every node.
+pub struct Graph<N> {
+    successors: RBMap<N, RBSet<N>>,
+    predecessors: RBMap<N, RBSet<N>>,
+}
+
+impl<N> Graph<N>
+where
+    N: Clone + PartialOrd,
+{
+    pub fn new() -> Self {
+        Graph { successors: RBMap::new(), predecessors: RBMap::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    pub fn contains_node(&self, n: &N) -> bool {
+        self.successors.contains_key(n)
+    }
+
+    pub fn contains_edge(&self, from: &N, to: &N) -> bool {
+        self.successors.get(from).is_some_and(|successors| successors.contains(to))
+    }
+
+    /// Returns a graph that contains `n`.
+    ///
+    /// Complexity: O(log n)
+    pub fn with_node(&self, n: N) -> Self {
+        if self.contains_node(&n) {
+            return self.clone();
+        }
+        Graph {
+            successors: self.successors.inserted(n.clone(), RBSet::new()),
+            predecessors: self.predecessors.inserted(n, RBSet::new()),
+        }
+    }
+
+    /// Returns a graph with an edge from `from` to `to`, adding the
+    /// nodes if needed.
+    ///
+    /// Complexity: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::graph::Graph;
+    ///
+    /// let g1 = Graph::new().with_edge("S", "NP");
+    /// let g2 = g1.with_edge("S", "VP");
+    ///
+    /// assert!(g2.contains_edge(&"S", &"VP"));
+    /// assert!(!g1.contains_edge(&"S", &"VP"));
+    /// assert!(g2.contains_node(&"VP"));
+    /// ```
+    pub fn with_edge(&self, from: N, to: N) -> Self {
+        let graph = self.with_node(to.clone());
+        let successors = graph.successors.updated(from.clone(), |successors| {
+            successors.map_or_else(RBSet::new, RBSet::clone).inserted(to.clone())
+        });
+        let predecessors = graph
+            .predecessors
+            .updated(to, |predecessors| {
+                predecessors.map_or_else(RBSet::new, RBSet::clone).inserted(from.clone())
+            })
+            .updated(from, |predecessors| predecessors.map_or_else(RBSet::new, RBSet::clone));
+        Graph { successors, predecessors }
+    }
+
+    /// Returns a graph without the edge from `from` to `to`.
+    ///
+    /// Complexity: O(log n)
+    pub fn without_edge(&self, from: &N, to: &N) -> Self {
+        if !self.contains_edge(from, to) {
+            return self.clone();
+        }
+        Graph {
+            successors: self.successors.updated(from.clone(), |successors| {
+                successors.map_or_else(RBSet::new, |successors| successors.removed(to))
+            }),
+            predecessors: self.predecessors.updated(to.clone(), |predecessors| {
+                predecessors.map_or_else(RBSet::new, |predecessors| predecessors.removed(from))
+            }),
+        }
+    }
+
+    /// Returns a graph without `n` and its edges.
+    ///
+    /// Complexity: O(d log n), where d is the number of edges of `n`.
+    pub fn without_node(&self, n: &N) -> Self {
+        if !self.contains_node(n) {
+            return self.clone();
+        }
+        let mut successors = self.successors.removed(n);
+        let mut predecessors = self.predecessors.removed(n);
+        for from in self.predecessors(n) {
+            if from != n {
+                successors = successors.updated(from.clone(), |s| {
+                    s.map_or_else(RBSet::new, |s| s.removed(n))
+                });
+            }
+        }
+        for to in self.successors(n) {
+            if to != n {
+                predecessors = predecessors.updated(to.clone(), |p| {
+                    p.map_or_else(RBSet::new, |p| p.removed(n))
+                });
+            }
+        }
+        Graph { successors, predecessors }
+    }
+
+    /// Returns the nodes in breadth-first order from `start`.
+    pub fn bfs<'a>(&'a self, start: &'a N) -> Bfs<'a, N> {
+        let mut queue = VecDeque::new();
+        let mut visited = RBSet::new();
+        if self.contains_node(start) {
+            queue.push_back(start);
+            visited = visited.inserted(start);
+        }
+        Bfs { graph: self, queue, visited }
+    }
+
+    /// Returns the nodes in depth-first pre-order from `start`.
+    pub fn dfs<'a>(&'a self, start: &'a N) -> Dfs<'a, N> {
+        let mut stack = Vec::new();
+        if self.contains_node(start) {
+            stack.push(start);
+        }
+        Dfs { graph: self, stack, visited: RBSet::new() }
+    }
+
+    /// Returns `true` if there is a path from `from` to `to`.
+    pub fn is_reachable(&self, from: &N, to: &N) -> bool {
+        self.bfs(from).any(|n| n == to)
+    }
+
+    /// Returns the nodes in an order where every edge goes from an
+    /// earlier to a later node, or `None` if the graph has a cycle.
+    ///
+    /// Among the nodes that could come next, the least comes first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::graph::Graph;
+    ///
+    /// let g = Graph::new().with_edge(3, 1).with_edge(1, 2).with_edge(3, 2);
+    ///
+    /// assert_eq!(g.topological_sort(), Some(vec!(&3, &1, &2)));
+    /// assert_eq!(g.with_edge(2, 3).topological_sort(), None);
+    /// ```
+    pub fn topological_sort(&self) -> Option<Vec<&N>> {
+        let mut in_degrees: RBMap<&N, usize> = RBMap::new();
+        let mut ready: RBSet<&N> = RBSet::new();
+        for (n, predecessors) in self.predecessors.iter() {
+            let in_degree = predecessors.iter().count();
+            if in_degree == 0 {
+                ready = ready.inserted(n);
+            }
+            in_degrees = in_degrees.inserted(n, in_degree);
+        }
+        let mut order = Vec::new();
+        while let Some(n) = ready.iter().next().copied() {
+            ready = ready.removed(&n);
+            order.push(n);
+            for m in self.successors(n) {
+                let in_degree = in_degrees.get(&m).copied().unwrap_or(0) - 1;
+                if in_degree == 0 {
+                    ready = ready.inserted(m);
+                }
+                in_degrees = in_degrees.inserted_or_replaced(m, in_degree);
+            }
+        }
+        if order.len() == in_degrees.iter().count() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the strongly connected components, with every component
+    /// before the components it has edges to.
+    ///
+    /// The nodes of a component are in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::graph::Graph;
+    ///
+    /// let g = Graph::new()
+    ///     .with_edge("a", "b")
+    ///     .with_edge("b", "a")
+    ///     .with_edge("b", "c");
+    ///
+    /// assert_eq!(
+    ///     g.strongly_connected_components(),
+    ///     vec!(vec!(&"a", &"b"), vec!(&"c"))
+    /// );
+    /// ```
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&N>> {
+        // Kosaraju: the nodes by decreasing finishing time, then
+        // depth-first searches on the reversed graph in that order.
+        let mut finished = Vec::new();
+        let mut visited = RBSet::new();
+        for start in self.nodes() {
+            if visited.contains(&start) {
+                continue;
+            }
+            visited = visited.inserted(start);
+            let mut stack = vec!((start, self.successors(start)));
+            while let Some((n, successors)) = stack.last_mut() {
+                match successors.next() {
+                    Some(m) if !visited.contains(&m) => {
+                        visited = visited.inserted(m);
+                        stack.push((m, self.successors(m)));
+                    },
+                    Some(_m) => {},
+                    None => {
+                        finished.push(*n);
+                        stack.pop();
+                    },
+                }
+            }
+        }
+
+        let mut components = Vec::new();
+        let mut assigned = RBSet::new();
+        for start in finished.into_iter().rev() {
+            if assigned.contains(&start) {
+                continue;
+            }
+            assigned = assigned.inserted(start);
+            let mut component = Vec::new();
+            let mut stack = vec!(start);
+            while let Some(n) = stack.pop() {
+                component.push(n);
+                for m in self.predecessors(n) {
+                    if !assigned.contains(&m) {
+                        assigned = assigned.inserted(m);
+                        stack.push(m);
+                    }
+                }
+            }
+            component.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            components.push(component);
+        }
+        components
+    }
+}
+
+impl<N> Graph<N> {
+    /// Returns an iterator over the nodes in ascending order.
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.successors.iter().map(|(n, _successors)| n)
+    }
+
+    /// Returns an iterator over the nodes with an edge from `n`,
+    /// in ascending order.
+    pub fn successors(&self, n: &N) -> rb_tree::Iter<'_, N>
+    where
+        N: Clone + PartialOrd,
+    {
+        neighbours(&self.successors, n)
+    }
+
+    /// Returns an iterator over the nodes with an edge to `n`,
+    /// in ascending order.
+    pub fn predecessors(&self, n: &N) -> rb_tree::Iter<'_, N>
+    where
+        N: Clone + PartialOrd,
+    {
+        neighbours(&self.predecessors, n)
+    }
+
+    /// Returns an iterator over the edges, ordered by their source.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N)> {
+        self.successors
+            .iter()
+            .flat_map(|(from, successors)| successors.iter().map(move |to| (from, to)))
+    }
+}
+
+fn neighbours<'a, N>(map: &'a RBMap<N, RBSet<N>>, n: &N) -> rb_tree::Iter<'a, N>
+where
+    N: Clone + PartialOrd,
+{
+    map.get(n).map_or_else(rb_tree::Iter::empty, RBSet::iter)
+}
+
+pub struct Bfs<'a, N> {
+    graph: &'a Graph<N>,
+    queue: VecDeque<&'a N>,
+    visited: RBSet<&'a N>,
+}
+
+impl<'a, N> Iterator for Bfs<'a, N>
+where
+    N: Clone + PartialOrd,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.queue.pop_front()?;
+        for m in self.graph.successors(n) {
+            if !self.visited.contains(&m) {
+                self.visited = self.visited.inserted(m);
+                self.queue.push_back(m);
+            }
+        }
+        Some(n)
+    }
+}
+
+pub struct Dfs<'a, N> {
+    graph: &'a Graph<N>,
+    stack: Vec<&'a N>,
+    visited: RBSet<&'a N>,
+}
+
+impl<'a, N> Iterator for Dfs<'a, N>
+where
+    N: Clone + PartialOrd,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(n) = self.stack.pop() {
+            if self.visited.contains(&n) {
+                continue;
+            }
+            self.visited = self.visited.inserted(n);
+            let successors: Vec<&N> = self.graph.successors(n).collect();
+            self.stack.extend(successors.into_iter().rev());
+            return Some(n);
+        }
+        None
+    }
+}
+
+impl<N> Clone for Graph<N>
+where
+    N: Clone,
+{
+    fn clone(&self) -> Self {
+        Graph {
+            successors: self.successors.clone(),
+            predecessors: self.predecessors.clone(),
+        }
+    }
+}
+
+impl<N> Default for Graph<N>
+where
+    N: Clone + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> Measure for Graph<N> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        for map in &[&self.successors, &self.predecessors] {
+            map.visit_nodes(visitor);
+            for (_n, neighbours) in map.iter() {
+                neighbours.visit_nodes(visitor);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(u32, u32)]) -> Graph<u32> {
+        edges.iter().fold(Graph::new(), |g, (from, to)| g.with_edge(*from, *to))
+    }
+
+    #[test]
+    fn new_creates_empty_graph() {
+        let g = Graph::<u32>::new();
+
+        assert!(g.is_empty());
+        assert_eq!(g.nodes().next(), None);
+        assert_eq!(g.successors(&1).next(), None);
+    }
+
+    #[test]
+    fn with_edge_adds_nodes_and_edge() {
+        let g = graph(&[(1, 2), (1, 3), (3, 1)]);
+
+        assert_eq!(g.nodes().collect::<Vec<_>>(), vec!(&1, &2, &3));
+        assert_eq!(g.successors(&1).collect::<Vec<_>>(), vec!(&2, &3));
+        assert_eq!(g.predecessors(&1).collect::<Vec<_>>(), vec!(&3));
+        assert_eq!(g.edges().collect::<Vec<_>>(), vec!((&1, &2), (&1, &3), (&3, &1)));
+        assert!(!g.contains_edge(&2, &1));
+    }
+
+    #[test]
+    fn without_edge_keeps_nodes() {
+        let g1 = graph(&[(1, 2)]);
+
+        let g2 = g1.without_edge(&1, &2);
+
+        assert!(g1.contains_edge(&1, &2));
+        assert!(!g2.contains_edge(&1, &2));
+        assert!(g2.contains_node(&2));
+        assert_eq!(g2.predecessors(&2).next(), None);
+    }
+
+    #[test]
+    fn without_node_removes_edges() {
+        let g1 = graph(&[(1, 2), (2, 3), (3, 2), (2, 2)]);
+
+        let g2 = g1.without_node(&2);
+
+        assert_eq!(g2.nodes().collect::<Vec<_>>(), vec!(&1, &3));
+        assert_eq!(g2.edges().next(), None);
+        assert_eq!(g2.predecessors(&3).next(), None);
+        assert_eq!(g1.edges().count(), 4);
+    }
+
+    #[test]
+    fn bfs_and_dfs_visit_reachable_nodes() {
+        let g = graph(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 1), (5, 1)]);
+
+        assert_eq!(g.bfs(&1).collect::<Vec<_>>(), vec!(&1, &2, &3, &4));
+        assert_eq!(g.dfs(&1).collect::<Vec<_>>(), vec!(&1, &2, &4, &3));
+        assert_eq!(g.bfs(&6).next(), None);
+        assert!(g.is_reachable(&5, &4));
+        assert!(!g.is_reachable(&1, &5));
+    }
+
+    #[test]
+    fn topological_sort_orders_dag() {
+        let g = graph(&[(5, 1), (1, 2), (4, 2), (2, 3)]);
+
+        assert_eq!(g.topological_sort(), Some(vec!(&4, &5, &1, &2, &3)));
+        assert_eq!(g.with_edge(3, 1).topological_sort(), None);
+        assert_eq!(g.with_edge(3, 3).topological_sort(), None);
+    }
+
+    #[test]
+    fn strongly_connected_components_in_topological_order() {
+        let g = graph(&[(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 6)]);
+
+        assert_eq!(
+            g.strongly_connected_components(),
+            vec!(vec!(&6), vec!(&1, &2, &3), vec!(&4, &5))
+        );
+    }
+
+    #[test]
+    fn long_path_does_not_overflow() {
+        let g = (0..10_000).fold(Graph::new(), |g, n| g.with_edge(n, n + 1));
+
+        assert_eq!(g.strongly_connected_components().len(), 10_001);
+        assert_eq!(g.dfs(&0).count(), 10_001);
+    }
+}
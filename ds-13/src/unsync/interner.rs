@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::unsync::list::List;
+use crate::unsync::tree::Tree;
+
+/// An interning table that hash-conses `List` and `Tree` nodes.
+///
+/// Every node built through the same `Interner` is maximally shared:
+/// structurally equal lists and trees are represented by the same
+/// node, so equality can be decided with `ptr_eq` in O(1).
+///
+/// The table keeps every interned node alive until the `Interner` is
+/// dropped. Values built by an `Interner` stay valid after that, but
+/// are no longer shared with values built afterwards. Use
+/// `with_interner` to limit the table to a scope.
+pub struct Interner<T> {
+    lists: HashMap<(T, usize), List<T>>,
+    trees: HashMap<(T, usize), Tree<T>>,
+    children: HashMap<(usize, usize), List<Tree<T>>>,
+    empty_tree: Option<Tree<T>>,
+}
+
+impl<T> Interner<T>
+where
+    T: Eq + Hash + Clone,
+{
+    pub fn new() -> Self {
+        Interner {
+            lists: HashMap::new(),
+            trees: HashMap::new(),
+            children: HashMap::new(),
+            empty_tree: None,
+        }
+    }
+
+    /// Returns the number of distinct nodes in the table.
+    pub fn len(&self) -> usize {
+        self.lists.len()
+            + self.trees.len()
+            + self.children.len()
+            + self.empty_tree.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Creates a list with `element` as head and `tail` as tail.
+    ///
+    /// `tail` should itself be built by this interner, otherwise the
+    /// result is only shared with lists consed onto the same `tail`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::List;
+    /// use ds_13::unsync::interner::Interner;
+    ///
+    /// let mut interner = Interner::new();
+    /// let l1 = interner.cons(1, &List::new());
+    /// let l2 = interner.cons(1, &List::new());
+    ///
+    /// assert!(l1.ptr_eq(&l2));
+    /// ```
+    pub fn cons(&mut self, element: T, tail: &List<T>) -> List<T> {
+        self.lists
+            .entry((element.clone(), tail.head_ptr()))
+            .or_insert_with(|| List::cons(element, tail))
+            .clone()
+    }
+
+    /// Returns the interned version of `list`.
+    pub fn list(&mut self, list: &List<T>) -> List<T> {
+        let elements: Vec<&T> = list.iter().collect();
+        let mut result = List::new();
+        for element in elements.into_iter().rev() {
+            result = self.cons(element.clone(), &result);
+        }
+        result
+    }
+
+    /// Returns the interned empty tree.
+    pub fn empty_tree(&mut self) -> Tree<T> {
+        self.empty_tree.get_or_insert_with(Tree::new).clone()
+    }
+
+    pub fn leaf(&mut self, x: T) -> Tree<T> {
+        self.tree(x, &List::new())
+    }
+
+    /// Creates a tree with root `x` and the given children.
+    ///
+    /// The children should themselves be built by this interner,
+    /// the list of children need not be.
+    pub fn tree(&mut self, x: T, children: &List<Tree<T>>) -> Tree<T> {
+        let children = self.children(children);
+        self.trees
+            .entry((x.clone(), children.head_ptr()))
+            .or_insert_with(|| Tree::tree(x, &children))
+            .clone()
+    }
+
+    /// Returns the interned version of `tree`, interning every subtree.
+    pub fn intern_tree(&mut self, tree: &Tree<T>) -> Tree<T> {
+        match tree.root() {
+            None => self.empty_tree(),
+            Some(x) => {
+                let mut children = List::new();
+                let subtrees: Vec<&Tree<T>> = tree.children().iter().collect();
+                for subtree in subtrees.into_iter().rev() {
+                    let subtree = self.intern_tree(subtree);
+                    children = self.cons_child(subtree, &children);
+                }
+                self.tree(x.clone(), &children)
+            }
+        }
+    }
+
+    fn children(&mut self, children: &List<Tree<T>>) -> List<Tree<T>> {
+        let subtrees: Vec<&Tree<T>> = children.iter().collect();
+        let mut result = List::new();
+        for subtree in subtrees.into_iter().rev() {
+            result = self.cons_child(subtree.clone(), &result);
+        }
+        result
+    }
+
+    fn cons_child(&mut self, subtree: Tree<T>, tail: &List<Tree<T>>) -> List<Tree<T>> {
+        self.children
+            .entry((subtree.root_ptr(), tail.head_ptr()))
+            .or_insert_with(|| List::cons(subtree, tail))
+            .clone()
+    }
+}
+
+impl<T> Default for Interner<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `f` with a fresh `Interner` that is dropped when `f` returns.
+///
+/// # Examples
+///
+/// ```
+/// use ds_13::unsync::interner::with_interner;
+///
+/// let same = with_interner(|interner| {
+///     let a = interner.leaf("NP");
+///     let b = interner.leaf("NP");
+///     a.ptr_eq(&b)
+/// });
+/// assert!(same);
+/// ```
+pub fn with_interner<T, R>(f: impl FnOnce(&mut Interner<T>) -> R) -> R
+where
+    T: Eq + Hash + Clone,
+{
+    let mut interner = Interner::new();
+    f(&mut interner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::measure;
+    use crate::unsynced_list;
+
+    #[test]
+    fn cons_shares_equal_lists() {
+        let mut interner = Interner::new();
+
+        let l1 = interner.cons(2, &List::new());
+        let l1 = interner.cons(1, &l1);
+        let l2 = interner.cons(2, &List::new());
+        let l2 = interner.cons(1, &l2);
+
+        assert!(l1.ptr_eq(&l2));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn list_interns_existing_list() {
+        let mut interner = Interner::new();
+        let l1 = unsynced_list!(3, 2, 1);
+        let l2 = unsynced_list!(3, 2, 1);
+
+        let i1 = interner.list(&l1);
+        let i2 = interner.list(&l2);
+
+        assert!(!l1.ptr_eq(&l2));
+        assert!(i1.ptr_eq(&i2));
+        assert_eq!(i1, l1);
+    }
+
+    #[test]
+    fn list_interns_shared_suffixes() {
+        let mut interner = Interner::new();
+
+        let l1 = interner.list(&unsynced_list!(3, 2, 1));
+        let l2 = interner.list(&unsynced_list!(3, 2, 0));
+
+        assert!(l1.popped_front().ptr_eq(&l2.popped_front()));
+        assert_eq!(measure(&[&l1, &l2]).nodes, 4);
+    }
+
+    #[test]
+    fn tree_shares_equal_subtrees() {
+        let mut interner = Interner::new();
+
+        let np1 = interner.leaf("NP");
+        let np2 = interner.leaf("NP");
+        let vp = interner.leaf("VP");
+        let s1 = interner.tree("S", &unsynced_list!(vp.clone(), np1));
+        let s2 = interner.tree("S", &unsynced_list!(vp, np2));
+
+        assert!(s1.ptr_eq(&s2));
+        assert!(!s1.ptr_eq(&interner.leaf("S")));
+    }
+
+    #[test]
+    fn intern_tree_shares_separately_built_trees() {
+        let build = || Tree::tree(
+            "S",
+            &unsynced_list!(
+                Tree::tree("VP", &unsynced_list!(Tree::leaf("sees"))),
+                Tree::tree("NP", &unsynced_list!(Tree::leaf("lion")))
+            )
+        );
+        let t1 = build();
+        let t2 = build();
+
+        with_interner(|interner| {
+            let i1 = interner.intern_tree(&t1);
+            let i2 = interner.intern_tree(&t2);
+
+            assert!(!t1.ptr_eq(&t2));
+            assert!(i1.ptr_eq(&i2));
+            assert_eq!(i1, t1);
+            assert_eq!(measure(&[&i1, &i2]).nodes, measure(&[&i1]).nodes);
+        });
+    }
+
+    #[test]
+    fn intern_tree_shares_empty_tree() {
+        let mut interner = Interner::<i32>::new();
+
+        let t1 = interner.intern_tree(&Tree::new());
+        let t2 = interner.empty_tree();
+
+        assert!(t1.ptr_eq(&t2));
+        assert!(t1.is_empty());
+    }
+
+    #[test]
+    fn values_outlive_interner() {
+        let (t1, t2) = with_interner(|interner| {
+            (interner.leaf(1), interner.leaf(1))
+        });
+
+        assert!(t1.ptr_eq(&t2));
+        assert!(!t1.ptr_eq(&with_interner(|interner| interner.leaf(1))));
+        assert_eq!(t1, Tree::leaf(1));
+    }
+}
@@ -0,0 +1,453 @@
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{OnceCell, RefCell};
+use crate::unsync::list::List;
+use crate::Error;
+
+/// A persistent list whose tails are computed on demand.
+///
+/// Every cell is a thunk that is evaluated at most once, the result
+/// is memoized and shared by all clones. This makes it possible to
+/// work with infinite lists, as long as only a finite prefix is forced.
+pub struct LazyList<T> {
+    node: Rc<LazyNode<T>>,
+}
+
+type Step<T> = Option<(T, LazyList<T>)>;
+type Thunk<T> = Box<dyn FnOnce() -> Step<T>>;
+
+struct LazyNode<T> {
+    value: OnceCell<Step<T>>,
+    thunk: RefCell<Option<Thunk<T>>>,
+}
+
+impl<T> LazyList<T> {
+    /// Creates an empty `LazyList`.
+    pub fn new() -> Self {
+        LazyList::evaluated(None)
+    }
+
+    /// Creates a list with the element given as head
+    /// and the provided list as tail.
+    ///
+    /// Complexity: O(1)
+    pub fn cons(element: T, tail: &LazyList<T>) -> Self {
+        LazyList::evaluated(Some((element, tail.clone())))
+    }
+
+    fn evaluated(step: Step<T>) -> Self {
+        let value = OnceCell::new();
+        let _ = value.set(step);
+        LazyList {
+            node: Rc::new(LazyNode { value, thunk: RefCell::new(None) }),
+        }
+    }
+
+    fn delayed(f: impl FnOnce() -> Step<T> + 'static) -> Self {
+        LazyList {
+            node: Rc::new(LazyNode {
+                value: OnceCell::new(),
+                thunk: RefCell::new(Some(Box::new(f))),
+            }),
+        }
+    }
+
+    /// Evaluates the first cell, if it hasn't been evaluated before.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the cell is forced while it is evaluated,
+    /// i.e. if the list is defined in terms of itself.
+    fn force(&self) -> &Step<T> {
+        self.node.value.get_or_init(|| {
+            let thunk = self.node.thunk.borrow_mut().take();
+            match thunk {
+                Some(f) => f(),
+                None => panic!("LazyList forced while being evaluated"),
+            }
+        })
+    }
+
+    /// Returns `true` if the first cell has been evaluated.
+    pub fn is_evaluated(&self) -> bool {
+        self.node.value.get().is_some()
+    }
+
+    /// Provides a reference to the front element, or
+    /// `None` if the list is empty.
+    ///
+    /// Forces the first cell.
+    pub fn front(&self) -> Option<&T> {
+        self.force().as_ref().map(|(element, _tail)| element)
+    }
+
+    /// Returns `true` if this `LazyList` is empty.
+    ///
+    /// Forces the first cell.
+    pub fn is_empty(&self) -> bool {
+        self.force().is_none()
+    }
+
+    /// Returns the tail of the list.
+    ///
+    /// Forces the first cell, but not the tail.
+    ///
+    /// # Panics
+    ///
+    /// This method panics when called on an empty list.
+    pub fn popped_front(&self) -> LazyList<T> {
+        match self.try_popped_front() {
+            Err(_) => panic!("You can't pop an empty list!"),
+            Ok(tail) => tail,
+        }
+    }
+
+    /// Returns the tail of the list, or `Error::EmptyList` if the
+    /// list is empty.
+    ///
+    /// Forces the first cell, but not the tail.
+    pub fn try_popped_front(&self) -> Result<LazyList<T>, Error> {
+        match self.force() {
+            None => Err(Error::EmptyList),
+            Some((_element, tail)) => Ok(tail.clone()),
+        }
+    }
+
+    pub fn pushed_front(&self, value: T) -> LazyList<T> {
+        LazyList::cons(value, self)
+    }
+
+    /// Returns an iterator over the elements, forcing them one at a time.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { next: Some(self) }
+    }
+}
+
+impl<T: Clone + 'static> LazyList<T> {
+    /// Creates a list that is computed by `f` when first forced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::lazy_list::LazyList;
+    ///
+    /// let list = LazyList::lazy(|| LazyList::cons(1, &LazyList::new()));
+    /// assert!(!list.is_evaluated());
+    ///
+    /// assert_eq!(list.front(), Some(&1));
+    /// assert!(list.is_evaluated());
+    /// ```
+    pub fn lazy(f: impl FnOnce() -> LazyList<T> + 'static) -> Self {
+        LazyList::delayed(|| f().force().clone())
+    }
+
+    /// Creates the infinite list `x, f(x), f(f(x)), ...`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::lazy_list::LazyList;
+    ///
+    /// let powers = LazyList::iterate(1, |x| x * 2);
+    ///
+    /// assert_eq!(powers.take(5).iter().cloned().collect::<Vec<_>>(), vec!(1, 2, 4, 8, 16));
+    /// ```
+    pub fn iterate(x: T, f: impl Fn(&T) -> T + 'static) -> Self {
+        iterate_rc(x, Rc::new(f))
+    }
+
+    /// Creates the infinite list `x, x, x, ...`.
+    pub fn repeat(x: T) -> Self {
+        LazyList::iterate(x, T::clone)
+    }
+
+    /// Creates the infinite list that repeats the elements of `list`,
+    /// or an empty list if `list` is empty.
+    pub fn cycle(list: &List<T>) -> Self {
+        cycle_from(list.clone(), list.clone())
+    }
+
+    /// Creates a `LazyList` with the elements of `list`.
+    ///
+    /// No elements are copied before they are forced.
+    pub fn from_list(list: &List<T>) -> Self {
+        let list = list.clone();
+        LazyList::delayed(move || {
+            list.front().map(|x| (x.clone(), LazyList::from_list(&list.popped_front())))
+        })
+    }
+
+    /// Forces every element and returns them as a `List`.
+    ///
+    /// This never returns for an infinite list.
+    pub fn to_list(&self) -> List<T> {
+        let elements: Vec<&T> = self.iter().collect();
+        elements.into_iter().rev().fold(List::new(), |acc, x| acc.pushed_front(x.clone()))
+    }
+
+    /// Returns a list of the first `n` elements, without forcing any.
+    pub fn take(&self, n: usize) -> Self {
+        if n == 0 {
+            return LazyList::new();
+        }
+        let list = self.clone();
+        LazyList::delayed(move || {
+            list.force().as_ref().map(|(x, tail)| (x.clone(), tail.take(n - 1)))
+        })
+    }
+
+    /// Returns the list without its first `n` elements, without forcing any.
+    pub fn drop(&self, n: usize) -> Self {
+        let list = self.clone();
+        LazyList::lazy(move || {
+            let mut list = list;
+            for _ in 0..n {
+                if list.is_empty() {
+                    break;
+                }
+                list = list.popped_front();
+            }
+            list
+        })
+    }
+
+    /// Returns a list with `f` applied to every element, applying `f`
+    /// only when an element is forced.
+    pub fn fmap<U: 'static>(&self, f: impl Fn(&T) -> U + 'static) -> LazyList<U> {
+        fmap_rc(self.clone(), Rc::new(f))
+    }
+
+    /// Returns a list of the elements that satisfy `p`.
+    ///
+    /// Forcing a cell forces the elements of `self` until the next
+    /// element that satisfies `p`, so forcing a cell of an infinite
+    /// list never returns if no further element satisfies `p`.
+    pub fn filter(&self, p: impl Fn(&T) -> bool + 'static) -> Self {
+        filter_rc(self.clone(), Rc::new(p))
+    }
+}
+
+fn iterate_rc<T: Clone + 'static>(x: T, f: Rc<dyn Fn(&T) -> T>) -> LazyList<T> {
+    LazyList::delayed(move || {
+        let next = f(&x);
+        Some((x, iterate_rc(next, f)))
+    })
+}
+
+fn cycle_from<T: Clone + 'static>(rest: List<T>, list: List<T>) -> LazyList<T> {
+    LazyList::delayed(move || {
+        let rest = if rest.is_empty() { list.clone() } else { rest };
+        rest.front().map(|x| (x.clone(), cycle_from(rest.popped_front(), list)))
+    })
+}
+
+fn fmap_rc<T, U>(list: LazyList<T>, f: Rc<dyn Fn(&T) -> U>) -> LazyList<U>
+where
+    T: Clone + 'static,
+    U: 'static,
+{
+    LazyList::delayed(move || {
+        list.force().as_ref().map(|(x, tail)| (f(x), fmap_rc(tail.clone(), f.clone())))
+    })
+}
+
+fn filter_rc<T: Clone + 'static>(list: LazyList<T>, p: Rc<dyn Fn(&T) -> bool>) -> LazyList<T> {
+    LazyList::delayed(move || {
+        let mut list = list;
+        loop {
+            let next = match list.force() {
+                None => return None,
+                Some((x, tail)) if p(x) => {
+                    return Some((x.clone(), filter_rc(tail.clone(), p.clone())));
+                },
+                Some((_x, tail)) => tail.clone(),
+            };
+            list = next;
+        }
+    })
+}
+
+impl<T> Default for LazyList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for LazyList<T> {
+    fn clone(&self) -> Self {
+        LazyList { node: Rc::clone(&self.node) }
+    }
+}
+
+impl<T> Drop for LazyList<T> {
+    fn drop(&mut self) {
+        let mut step = Rc::get_mut(&mut self.node).and_then(|node| node.value.take());
+        while let Some(Some((_element, mut tail))) = step {
+            step = Rc::get_mut(&mut tail.node).and_then(|node| node.value.take());
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LazyList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a LazyList<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next.map(LazyList::force) {
+            Some(Some((element, tail))) => {
+                self.next = Some(tail);
+                Some(element)
+            },
+            _ => {
+                self.next = None;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use crate::unsynced_list;
+
+    fn to_vec<T: Clone>(list: &LazyList<T>) -> Vec<T> {
+        list.iter().cloned().collect()
+    }
+
+    #[test]
+    fn new_creates_empty_list() {
+        let list = LazyList::<i32>::new();
+
+        assert!(list.is_evaluated());
+        assert!(list.is_empty());
+        assert_eq!(list.front(), None);
+    }
+
+    #[test]
+    fn cons_creates_evaluated_list() {
+        let list = LazyList::cons(1, &LazyList::new()).pushed_front(0);
+
+        assert_eq!(list.front(), Some(&0));
+        assert_eq!(list.popped_front().front(), Some(&1));
+        assert_eq!(to_vec(&list), vec!(0, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn popped_front_on_empty_list_panics() {
+        LazyList::<i32>::new().popped_front();
+    }
+
+    #[test]
+    fn try_popped_front_returns_error_on_empty_list() {
+        let list = LazyList::new().pushed_front(1);
+
+        assert!(list.try_popped_front().unwrap().is_empty());
+        assert!(LazyList::<i32>::new().try_popped_front().is_err());
+    }
+
+    #[test]
+    fn lazy_is_evaluated_once() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let list = LazyList::lazy(move || {
+            counter.set(counter.get() + 1);
+            LazyList::cons(1, &LazyList::new())
+        });
+        let clone = list.clone();
+
+        assert_eq!(calls.get(), 0);
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(clone.front(), Some(&1));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn iterate_creates_infinite_list() {
+        let naturals = LazyList::iterate(0, |x| x + 1);
+
+        assert_eq!(to_vec(&naturals.take(4)), vec!(0, 1, 2, 3));
+        assert_eq!(naturals.drop(100).front(), Some(&100));
+    }
+
+    #[test]
+    fn repeat_creates_infinite_list() {
+        let list = LazyList::repeat("a");
+
+        assert_eq!(to_vec(&list.take(3)), vec!("a", "a", "a"));
+    }
+
+    #[test]
+    fn cycle_repeats_list() {
+        let list = LazyList::cycle(&unsynced_list!(2, 1));
+
+        assert_eq!(to_vec(&list.take(5)), vec!(1, 2, 1, 2, 1));
+        assert!(LazyList::cycle(&List::<i32>::new()).is_empty());
+    }
+
+    #[test]
+    fn fmap_applies_function_on_demand() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let squares = LazyList::iterate(1, |x| x + 1).fmap(move |x| {
+            counter.set(counter.get() + 1);
+            x * x
+        });
+
+        assert_eq!(calls.get(), 0);
+        assert_eq!(to_vec(&squares.take(3)), vec!(1, 4, 9));
+        assert_eq!(to_vec(&squares.take(3)), vec!(1, 4, 9));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn filter_works_on_infinite_list() {
+        let evens = LazyList::iterate(0, |x| x + 1).filter(|x| x % 2 == 0);
+
+        assert_eq!(to_vec(&evens.take(3)), vec!(0, 2, 4));
+    }
+
+    #[test]
+    fn take_does_not_force() {
+        let list = LazyList::iterate(0, |x| x + 1);
+
+        let prefix = list.take(2);
+
+        assert!(!list.is_evaluated());
+        assert!(!prefix.is_evaluated());
+        assert!(list.take(0).is_empty());
+    }
+
+    #[test]
+    fn convert_from_and_to_list() {
+        let list = unsynced_list!(3, 2, 1);
+
+        let lazy = LazyList::from_list(&list);
+
+        assert!(!lazy.is_evaluated());
+        assert_eq!(lazy.to_list(), list);
+        assert_eq!(LazyList::iterate(1, |x| x + 1).take(3).to_list(), list);
+    }
+
+    #[test]
+    fn drop_long_evaluated_list() {
+        let list = LazyList::iterate(0, |x| x + 1).take(200_000);
+
+        assert_eq!(list.iter().count(), 200_000);
+    }
+}
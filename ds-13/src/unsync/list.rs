@@ -1,9 +1,15 @@
-use std::fmt::Debug;
-use std::fmt;
-use std::rc::Rc;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
 use itertools::{EitherOrBoth, Itertools};
+use crate::stats::{Measure, NodeVisitor};
+use crate::traits::{Filterable, Foldable, Functor, Monad};
+use crate::Error;
 
-#[derive(Debug)]
 pub struct List<T> {
     head: Option<Rc<Node<T>>>,
 }
@@ -97,6 +103,33 @@ impl<T> List<T>
         self.head.is_none()
     }
 
+    /// Returns `true` if both lists share the same head node.
+    ///
+    /// Lists built by the same `Interner` are equal exactly when
+    /// they share the same head node.
+    ///
+    /// Complexity: O(1)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::List;
+    ///
+    /// let l1 = List::cons(1, &List::new());
+    /// let l2 = l1.clone();
+    /// let l3 = List::cons(1, &List::new());
+    ///
+    /// assert!(l1.ptr_eq(&l2));
+    /// assert!(!l1.ptr_eq(&l3));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.head_ptr() == other.head_ptr()
+    }
+
+    pub(crate) fn head_ptr(&self) -> usize {
+        self.head.as_ref().map_or(0, |node| Rc::as_ptr(node) as usize)
+    }
+
     /// Returns the tail of the list.
     ///
     /// # Panics
@@ -122,14 +155,48 @@ impl<T> List<T>
         List { head: self.head.as_ref().and_then(|node| node.next.clone()) }
     }
 
+    /// Returns the tail of the list, or `Error::EmptyList` if the
+    /// list is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::Error;
+    /// use ds_13::unsync::List;
+    ///
+    /// let list = List::from_value(5);
+    ///
+    /// assert_eq!(list.try_popped_front(), Ok(List::new()));
+    /// assert_eq!(List::<i32>::new().try_popped_front(), Err(Error::EmptyList));
+    /// ```
+    pub fn try_popped_front(&self) -> Result<List<T>, Error> {
+        match &self.head {
+            None => Err(Error::EmptyList),
+            Some(node) => Ok(List { head: node.next.clone() }),
+        }
+    }
+
     pub fn tail(&self) -> List<T> {
         self.popped_front()
     }
 
+    pub fn try_tail(&self) -> Result<List<T>, Error> {
+        self.try_popped_front()
+    }
+
     pub fn head_tail(&self) -> (Option<&T>, List<T>) {
         (self.front(), self.tail())
     }
 
+    /// Returns the front element and the tail, or `Error::EmptyList`
+    /// if the list is empty.
+    pub fn try_head_tail(&self) -> Result<(&T, List<T>), Error> {
+        match &self.head {
+            None => Err(Error::EmptyList),
+            Some(node) => Ok((&node.element, List { head: node.next.clone() })),
+        }
+    }
+
     pub fn pushed_front(&self, value: T) -> List<T> {
         List::cons(value, self)
     }
@@ -145,6 +212,224 @@ impl<T> List<T>
          Iter { next: self.head.as_ref().map(|node| &**node) }
      }
 
+    /// Returns the number of elements.
+    ///
+    /// Complexity: O(n)
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Returns a reference to the element at index `n`, or `None` if
+    /// the list is too short.
+    ///
+    /// Complexity: O(n)
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    /// Returns a reference to the last element, or `None` if the
+    /// list is empty.
+    ///
+    /// Complexity: O(n)
+    pub fn last(&self) -> Option<&T> {
+        self.iter().last()
+    }
+
+    /// Returns `true` if the list contains an element equal to `x`.
+    ///
+    /// Complexity: O(n)
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|y| y == x)
+    }
+
+    /// Returns the list without its first `n` elements.
+    ///
+    /// The result shares all its nodes with `self`.
+    ///
+    /// Complexity: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::List;
+    ///
+    /// let list = List::from_value(3).pushed_front(2).pushed_front(1);
+    ///
+    /// assert_eq!(list.drop(2), List::from_value(3));
+    /// assert!(list.drop(5).is_empty());
+    /// ```
+    pub fn drop(&self, n: usize) -> List<T> {
+        let mut link = &self.head;
+        for _ in 0..n {
+            match link {
+                None => break,
+                Some(node) => link = &node.next,
+            }
+        }
+        List { head: link.clone() }
+    }
+
+    /// Returns the first `n` elements.
+    ///
+    /// If the list has at most `n` elements, the result shares all
+    /// its nodes with `self`.
+    ///
+    /// Complexity: O(n)
+    pub fn take(&self, n: usize) -> List<T>
+    where
+        T: Clone,
+    {
+        self.split_at(n).0
+    }
+
+    /// Splits the list into its first `n` elements and the rest,
+    /// see `take` and `drop`.
+    ///
+    /// Complexity: O(n)
+    pub fn split_at(&self, n: usize) -> (List<T>, List<T>)
+    where
+        T: Clone,
+    {
+        let rest = self.drop(n);
+        if rest.is_empty() {
+            return (self.clone(), rest);
+        }
+        let front: Vec<&T> = self.iter().take(n).collect();
+        (prepended_all(front.into_iter().cloned(), List::new()), rest)
+    }
+
+    /// Returns a list of pairs of the elements of `self` and `other`,
+    /// as long as the shorter of the two.
+    ///
+    /// Complexity: O(n)
+    pub fn zip<U>(&self, other: &List<U>) -> List<(T, U)>
+    where
+        T: Clone,
+        U: Clone,
+    {
+        let pairs: Vec<(T, U)> = self.iter().cloned().zip(other.iter().cloned()).collect();
+        prepended_all(pairs.into_iter(), List::new())
+    }
+
+    /// Splits the list into the elements that satisfy `p` and the
+    /// elements that don't, keeping their order.
+    ///
+    /// The longest suffix where every element ends up in the same list
+    /// is shared with that list.
+    ///
+    /// Complexity: O(n)
+    pub fn partition(&self, mut p: impl FnMut(&T) -> bool) -> (List<T>, List<T>)
+    where
+        T: Clone,
+    {
+        let flags: Vec<bool> = self.iter().map(&mut p).collect();
+        let suffix_flag = match flags.last() {
+            None => return (List::new(), List::new()),
+            Some(flag) => *flag,
+        };
+        let split = flags.len() - flags.iter().rev().take_while(|flag| **flag == suffix_flag).count();
+        let (mut yes, mut no) = (Vec::new(), Vec::new());
+        for (x, flag) in self.iter().zip(&flags).take(split) {
+            if *flag {
+                yes.push(x);
+            } else {
+                no.push(x);
+            }
+        }
+        let (yes_tail, no_tail) = if suffix_flag {
+            (self.drop(split), List::new())
+        } else {
+            (List::new(), self.drop(split))
+        };
+        (
+            prepended_all(yes.into_iter().cloned(), yes_tail),
+            prepended_all(no.into_iter().cloned(), no_tail),
+        )
+    }
+
+    /// Removes consecutive repeated elements.
+    ///
+    /// The suffix after the last removed element is shared with `self`.
+    ///
+    /// Complexity: O(n)
+    pub fn dedup(&self) -> List<T>
+    where
+        T: Clone + PartialEq,
+    {
+        let elements: Vec<&T> = self.iter().collect();
+        match (1..elements.len()).rev().find(|&i| elements[i] == elements[i - 1]) {
+            None => self.clone(),
+            Some(last_removed) => {
+                let kept = elements[..last_removed]
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, x)| *i == 0 || elements[i - 1] != **x)
+                    .map(|(_i, x)| (*x).clone())
+                    .collect::<Vec<T>>();
+                prepended_all(kept.into_iter(), self.drop(last_removed + 1))
+            }
+        }
+    }
+
+    /// Sorts the list with a stable merge sort.
+    ///
+    /// The longest suffix that is already in its sorted position is
+    /// shared with `self`.
+    ///
+    /// Complexity: O(n log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::List;
+    ///
+    /// let list = List::from_value(4).pushed_front(1).pushed_front(3);
+    /// let sorted = list.sort_by(|a, b| a.cmp(b));
+    ///
+    /// assert_eq!(sorted.iter().collect::<Vec<_>>(), vec!(&1, &3, &4));
+    /// assert!(sorted.drop(2).ptr_eq(&list.drop(2)));
+    /// ```
+    pub fn sort_by(&self, mut compare: impl FnMut(&T, &T) -> Ordering) -> List<T>
+    where
+        T: Clone,
+    {
+        let elements: Vec<&T> = self.iter().collect();
+        let order = merge_sort(
+            (0..elements.len()).collect(),
+            &mut |i, j| compare(elements[i], elements[j])
+        );
+        let in_place = order
+            .iter()
+            .rev()
+            .zip((0..elements.len()).rev())
+            .take_while(|(i, j)| *i == j)
+            .count();
+        let split = elements.len() - in_place;
+        prepended_all(
+            order[..split].iter().map(|i| elements[*i].clone()),
+            self.drop(split)
+        )
+    }
+
+    /// Reports the nodes of the spine to `visitor` and calls
+    /// `on_element` for every element in a node not seen before.
+    pub(crate) fn visit_spine(
+        &self,
+        visitor: &mut NodeVisitor,
+        mut on_element: impl FnMut(&T, &mut NodeVisitor),
+    ) {
+        let mut link = &self.head;
+        while let Some(node) = link {
+            if !visitor.visit(node) {
+                break;
+            }
+            on_element(&node.element, visitor);
+            link = &node.next;
+        }
+    }
 }
 
 #[macro_export]
@@ -160,6 +445,44 @@ macro_rules! unsynced_list {
     };
 }
 
+impl<A: Clone, B: Clone> List<(A, B)> {
+    /// Splits a list of pairs into a list of first and a list of
+    /// second elements.
+    ///
+    /// Complexity: O(n)
+    pub fn unzip(&self) -> (List<A>, List<B>) {
+        let (xs, ys): (Vec<A>, Vec<B>) = self.iter().cloned().unzip();
+        (prepended_all(xs.into_iter(), List::new()), prepended_all(ys.into_iter(), List::new()))
+    }
+}
+
+/// Stable merge sort of `indices` by `compare`.
+fn merge_sort(
+    mut indices: Vec<usize>,
+    compare: &mut impl FnMut(usize, usize) -> Ordering
+) -> Vec<usize> {
+    if indices.len() <= 1 {
+        return indices;
+    }
+    let right = indices.split_off(indices.len() / 2);
+    let left = merge_sort(indices, compare).into_iter();
+    let right = merge_sort(right, compare).into_iter();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let (mut left, mut right) = (left.peekable(), right.peekable());
+    while let (Some(&i), Some(&j)) = (left.peek(), right.peek()) {
+        if compare(j, i) == Ordering::Less {
+            merged.push(j);
+            right.next();
+        } else {
+            merged.push(i);
+            left.next();
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    merged
+}
+
 impl<'a, T> IntoIterator for &'a List<T> 
 {
     type Item = &'a T;
@@ -193,7 +516,15 @@ impl<T> Clone for List<T>
     }
 }
 
-// impl<T: fmt::Debug> fmt::Debug for List<T> {}
+impl<T> fmt::Debug for List<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
 impl<T> fmt::Display for List<T> 
 where
     T: fmt::Display,
@@ -220,32 +551,154 @@ impl<T> Drop for List<T> {
     }
 }
 
+impl<T> Measure for List<T> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        self.visit_spine(visitor, |_element, _visitor| {});
+    }
+}
+
+impl<T> Foldable for List<T> {
+    type Item = T;
+
+    fn foldl<U>(&self, mut f: impl FnMut(U, &T) -> U, acc: U) -> U {
+        self.iter().fold(acc, &mut f)
+    }
+
+    fn foldr<U>(&self, mut f: impl FnMut(&T, U) -> U, acc: U) -> U {
+        let elements: Vec<&T> = self.iter().collect();
+        elements.into_iter().rev().fold(acc, |acc, x| f(x, acc))
+    }
+}
+
+impl<T> Functor for List<T> {
+    type Mapped<U> = List<U>;
+
+    fn fmap<U>(&self, mut f: impl FnMut(&T) -> U) -> List<U> {
+        let elements: Vec<U> = self.iter().map(&mut f).collect();
+        elements.into_iter().rev().fold(List::new(), |acc, x| acc.pushed_front(x))
+    }
+}
+
+impl<T: Clone> Filterable for List<T> {
+    fn filter(&self, p: impl FnMut(&T) -> bool) -> Self {
+        filter(p, self)
+    }
+}
+
+impl<T> Monad for List<T> {
+    fn mreturn(x: T) -> Self {
+        List::from_value(x)
+    }
+
+    fn and_then<U: Clone>(&self, f: impl FnMut(&T) -> List<U>) -> List<U> {
+        mbind(self, f)
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for List<T> {
+    /// Creates a list with the elements in iteration order.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let elements: Vec<T> = iter.into_iter().collect();
+        List::from(elements)
+    }
+}
+
+impl<T> From<Vec<T>> for List<T> {
+    /// Creates a list with the elements of the vector, the first
+    /// element in front.
+    fn from(elements: Vec<T>) -> Self {
+        prepended_all(elements.into_iter(), List::new())
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for List<T> {
+    fn from(elements: [T; N]) -> Self {
+        prepended_all(IntoIterator::into_iter(elements), List::new())
+    }
+}
+
 impl<T> PartialEq for List<T> 
 where
-    T: PartialEq + Clone,
+    T: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
+        if self.ptr_eq(other) {
+            return true;
+        }
         self.iter().zip_longest(other.iter()).all(|x| matches!(x, EitherOrBoth::Both(a, b) if a == b))
     }
 }
 
-pub fn filter<T: Copy>(
-    p: impl FnOnce(&T) -> bool + Copy, 
-    list: &List<T>
-) -> List<T> {
-    match list.front() {
-        Some(head) => {
-            let tail = filter(p, &list.popped_front());
-            if p(head) {
-                List::cons(*head, &tail)
-            } else {
-                tail
-            }
-        },
-        None => List::new()
-        
+impl<T: Eq> Eq for List<T> {}
+
+impl<T> PartialOrd for List<T>
+where
+    T: PartialOrd,
+{
+    /// Compares the lists lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for List<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for List<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for x in self {
+            x.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
     }
-} 
+}
+
+/// Returns a list of the elements of `list` that satisfy `p`.
+///
+/// The longest suffix of `list` where every element satisfies `p`
+/// is shared with the result, only the kept elements before it are
+/// cloned.
+pub fn filter<T: Clone>(mut p: impl FnMut(&T) -> bool, list: &List<T>) -> List<T> {
+    let mut kept = Vec::new();
+    let mut run = Vec::new();
+    let mut suffix = &list.head;
+    let mut link = &list.head;
+    while let Some(node) = link {
+        if p(&node.element) {
+            run.push(&node.element);
+        } else {
+            kept.append(&mut run);
+            suffix = &node.next;
+        }
+        link = &node.next;
+    }
+    prepended_all(kept.into_iter().cloned(), List { head: suffix.clone() })
+}
+
+/// Conses the elements onto `tail`, keeping their order.
+fn prepended_all<T>(elements: impl DoubleEndedIterator<Item = T>, tail: List<T>) -> List<T> {
+    elements.rev().fold(tail, |acc, x| List::cons(x, &acc))
+}
+
+/// Returns `front` followed by `tail`, sharing `tail`.
+fn appended<T: Clone>(front: &List<T>, tail: List<T>) -> List<T> {
+    if tail.is_empty() {
+        return front.clone();
+    }
+    let elements: Vec<&T> = front.iter().collect();
+    prepended_all(elements.into_iter().cloned(), tail)
+}
 
 pub fn reverse<T: Clone>(list: &List<T>) -> List<T> {
     foldl(
@@ -328,9 +781,59 @@ pub fn mreturn<T>(t: T) -> List<T>
     List::cons(t, &List::new())
 }
 
-pub fn mbind<A: Copy, B: Copy>(list: &List<A>, k: impl Fn(&A) -> List<B> + Copy) -> List<B> {
-    let list_list = fmap(k, list);
-    concat_all(&list_list)
+/// Maps every element to a list with `k` and concatenates the lists.
+///
+/// The list returned for the last element is shared with the result.
+pub fn mbind<A, B: Clone>(list: &List<A>, k: impl FnMut(&A) -> List<B>) -> List<B> {
+    let lists: Vec<List<B>> = list.iter().map(k).collect();
+    lists.into_iter().rev().fold(List::new(), |acc, xs| appended(&xs, acc))
+}
+
+// List Applicative
+/// Applies every function in `fs` to every element in `xs`.
+///
+/// # Examples
+///
+/// ```
+/// use ds_13::unsync::list::{ap, List};
+///
+/// let fs = List::<fn(&i32) -> i32>::from_value(|x| x * 10).pushed_front(|x| x + 1);
+/// let xs = List::from_value(2).pushed_front(1);
+///
+/// let ys: Vec<i32> = ap(&fs, &xs).iter().cloned().collect();
+/// assert_eq!(ys, vec!(2, 3, 10, 20));
+/// ```
+pub fn ap<F, A, B>(fs: &List<F>, xs: &List<A>) -> List<B>
+where
+    F: Fn(&A) -> B,
+    B: Clone,
+{
+    mbind(fs, |f| xs.fmap(f))
+}
+
+/// Applies `f` to every pair of elements from `xs` and `ys`.
+pub fn lift2<A, B, C: Clone>(
+    mut f: impl FnMut(&A, &B) -> C,
+    xs: &List<A>,
+    ys: &List<B>
+) -> List<C> {
+    mbind(xs, |x| ys.fmap(|y| f(x, y)))
+}
+
+/// Returns every list that takes one element from each list in `xss`.
+///
+/// The returned lists share their tails.
+pub fn sequence<A: Clone>(xss: &List<List<A>>) -> List<List<A>> {
+    xss.foldr(
+        |xs, acc: List<List<A>>| mbind(xs, |x| acc.fmap(|ys| List::cons(x.clone(), ys))),
+        mreturn(List::new())
+    )
+}
+
+/// Maps every element to a list of choices with `f` and returns
+/// every combination of choices, see `sequence`.
+pub fn traverse<A, B: Clone>(f: impl FnMut(&A) -> List<B>, xs: &List<A>) -> List<List<B>> {
+    sequence(&xs.fmap(f))
 }
 
 
@@ -384,6 +887,18 @@ mod tests {
         assert_eq!(l2.front(), Some(&6.7));
     }
 
+    #[test]
+    fn try_variants_return_error_on_empty_list() {
+        let empty = List::<i32>::new();
+        let list = unsynced_list!(2, 1);
+
+        assert_eq!(empty.try_popped_front(), Err(Error::EmptyList));
+        assert_eq!(empty.try_tail(), Err(Error::EmptyList));
+        assert_eq!(empty.try_head_tail(), Err(Error::EmptyList));
+        assert_eq!(list.try_tail(), Ok(List::from_value(2)));
+        assert_eq!(list.try_head_tail(), Ok((&1, List::from_value(2))));
+    }
+
     #[test]
     fn popped_front_returns_tail() {
         let l1 = List::new();
@@ -465,4 +980,240 @@ mod tests {
 
         assert_eq!(list, unsynced_list!(3));
     }
+
+    #[test]
+    fn filter_works_without_copy() {
+        let list = unsynced_list!("c".to_string(), "bb".to_string(), "a".to_string());
+
+        let short = filter(|s: &String| s.len() == 1, &list);
+
+        assert_eq!(short, unsynced_list!("c".to_string(), "a".to_string()));
+    }
+
+    #[test]
+    fn filter_shares_passing_suffix() {
+        let list = unsynced_list!(6, 4, 1, 2);
+
+        let evens = filter(|x| x % 2 == 0, &list);
+
+        assert_eq!(evens, unsynced_list!(6, 4, 2));
+        assert!(evens.popped_front().ptr_eq(&list.popped_front().popped_front()));
+        assert!(filter(|_| true, &list).ptr_eq(&list));
+    }
+
+    #[test]
+    fn mbind_concatenates_in_order() {
+        let list = unsynced_list!(2, 1);
+
+        let result = mbind(&list, |x| unsynced_list!(x.to_string(), "-".repeat(*x)));
+
+        assert_eq!(
+            result,
+            unsynced_list!(
+                "2".to_string(), "--".to_string(), "1".to_string(), "-".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn ap_applies_every_function() {
+        fn times_ten(x: &i32) -> i32 {
+            x * 10
+        }
+        fn plus_one(x: &i32) -> i32 {
+            x + 1
+        }
+        let fs = unsynced_list!(times_ten as fn(&i32) -> i32, plus_one);
+        let xs = unsynced_list!(2, 1);
+
+        assert_eq!(ap(&fs, &xs), unsynced_list!(20, 10, 3, 2));
+    }
+
+    #[test]
+    fn lift2_combines_every_pair() {
+        let xs = unsynced_list!("b", "a");
+        let ys = unsynced_list!(2, 1);
+
+        let result = lift2(|x, y| format!("{}{}", x, y), &xs, &ys);
+
+        assert_eq!(
+            result,
+            unsynced_list!(
+                "b2".to_string(), "b1".to_string(), "a2".to_string(), "a1".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn sequence_returns_cartesian_product() {
+        let xss = unsynced_list!(unsynced_list!(4, 3), unsynced_list!(2, 1));
+
+        let result = sequence(&xss);
+
+        assert_eq!(
+            result,
+            unsynced_list!(
+                unsynced_list!(4, 2), unsynced_list!(3, 2),
+                unsynced_list!(4, 1), unsynced_list!(3, 1)
+            )
+        );
+        assert_eq!(sequence(&List::<List<i32>>::new()), unsynced_list!(List::new()));
+        assert!(sequence(&unsynced_list!(List::<i32>::new(), unsynced_list!(1))).is_empty());
+    }
+
+    #[test]
+    fn sequence_shares_tails() {
+        let xss = unsynced_list!(unsynced_list!(4, 3), unsynced_list!(2, 1));
+
+        let result: Vec<List<i32>> = sequence(&xss).iter().cloned().collect();
+
+        assert!(result[0].popped_front().ptr_eq(&result[2].popped_front()));
+    }
+
+    #[test]
+    fn traverse_maps_and_sequences() {
+        let xs = unsynced_list!(2, 1);
+
+        let result = traverse(|x| unsynced_list!(-x, *x), &xs);
+
+        assert_eq!(result.iter().count(), 4);
+        assert_eq!(result.front(), Some(&unsynced_list!(2, 1)));
+    }
+
+    #[test]
+    fn len_nth_last_and_contains() {
+        let list = unsynced_list!(3, 2, 1);
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(List::<i32>::new().len(), 0);
+        assert_eq!(list.nth(0), Some(&1));
+        assert_eq!(list.nth(2), Some(&3));
+        assert_eq!(list.nth(3), None);
+        assert_eq!(list.last(), Some(&3));
+        assert_eq!(List::<i32>::new().last(), None);
+        assert!(list.contains(&2));
+        assert!(!list.contains(&4));
+    }
+
+    #[test]
+    fn drop_returns_shared_suffix() {
+        let list = unsynced_list!(3, 2, 1);
+
+        assert!(list.drop(0).ptr_eq(&list));
+        assert!(list.drop(1).ptr_eq(&list.popped_front()));
+        assert_eq!(list.drop(2), unsynced_list!(3));
+        assert!(list.drop(3).is_empty());
+        assert!(list.drop(4).is_empty());
+    }
+
+    #[test]
+    fn take_and_split_at() {
+        let list = unsynced_list!(3, 2, 1);
+
+        assert_eq!(list.take(2), unsynced_list!(2, 1));
+        assert!(list.take(3).ptr_eq(&list));
+        assert!(list.take(0).is_empty());
+
+        let (front, rest) = list.split_at(1);
+        assert_eq!(front, unsynced_list!(1));
+        assert!(rest.ptr_eq(&list.popped_front()));
+    }
+
+    #[test]
+    fn zip_and_unzip() {
+        let xs = unsynced_list!(3, 2, 1);
+        let ys = unsynced_list!("b", "a");
+
+        let pairs = xs.zip(&ys);
+        assert_eq!(pairs, unsynced_list!((2, "b"), (1, "a")));
+
+        let (xs, ys) = pairs.unzip();
+        assert_eq!(xs, unsynced_list!(2, 1));
+        assert_eq!(ys, unsynced_list!("b", "a"));
+    }
+
+    #[test]
+    fn partition_keeps_order_and_shares_suffix() {
+        let list = unsynced_list!(6, 4, 3, 2, 1);
+
+        let (evens, odds) = list.partition(|x| x % 2 == 0);
+
+        assert_eq!(evens, unsynced_list!(6, 4, 2));
+        assert_eq!(odds, unsynced_list!(3, 1));
+        assert!(evens.drop(1).ptr_eq(&list.drop(3)));
+
+        let (all, none) = list.partition(|_| true);
+        assert!(all.ptr_eq(&list));
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn dedup_removes_consecutive_duplicates() {
+        let list = unsynced_list!(3, 2, 1, 1, 2, 2);
+
+        let deduped = list.dedup();
+
+        assert_eq!(deduped, unsynced_list!(3, 2, 1, 2));
+        assert!(deduped.drop(2).ptr_eq(&list.drop(4)));
+        let unique = unsynced_list!(1, 2, 1);
+        assert!(unique.dedup().ptr_eq(&unique));
+    }
+
+    #[test]
+    fn sort_by_is_stable() {
+        let list = unsynced_list!((1, "c"), (0, "b"), (1, "a"), (0, "z"));
+
+        let sorted = list.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(sorted, unsynced_list!((1, "c"), (1, "a"), (0, "b"), (0, "z")));
+    }
+
+    #[test]
+    fn sort_by_shares_sorted_suffix() {
+        let sorted = unsynced_list!(5, 4, 3, 2, 1);
+        assert!(sorted.sort_by(|a, b| a.cmp(b)).ptr_eq(&sorted));
+
+        let list = unsynced_list!(5, 4, 1, 3, 2);
+        let result = list.sort_by(|a, b| a.cmp(b));
+        assert_eq!(result, sorted);
+        assert!(result.drop(3).ptr_eq(&list.drop(3)));
+        assert!(List::<i32>::new().sort_by(|a, b| a.cmp(b)).is_empty());
+    }
+
+    #[test]
+    fn from_vec_and_array_keep_order() {
+        let list = List::from(vec!(1, 2, 3));
+
+        assert_eq!(list.front(), Some(&1));
+        assert_eq!(list, List::from([1, 2, 3]));
+        assert_eq!(list, (1..4).collect::<List<_>>());
+        assert_eq!(List::<i32>::default(), List::new());
+    }
+
+    #[test]
+    fn debug_formats_elements() {
+        assert_eq!(format!("{:?}", List::from(["a", "b"])), r#"["a", "b"]"#);
+        assert_eq!(format!("{:?}", List::<i32>::new()), "[]");
+    }
+
+    #[test]
+    fn lists_compare_lexicographically() {
+        assert!(List::from([1, 2]) < List::from([1, 3]));
+        assert!(List::from([1, 2]) < List::from([1, 2, 0]));
+        assert!(List::new() < List::from([0]));
+        assert_eq!(List::from([2]).cmp(&List::from([1, 5])), Ordering::Greater);
+    }
+
+    #[test]
+    fn equal_lists_are_equal_keys() {
+        use std::collections::HashSet;
+
+        let shared = List::from([2, 3]);
+        let keys: HashSet<List<i32>> = vec!(shared.pushed_front(1), List::from([1, 2, 3]), shared)
+            .into_iter()
+            .collect();
+
+        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&List::from([2, 3])));
+    }
 }
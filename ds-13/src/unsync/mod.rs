@@ -2,5 +2,13 @@ pub mod tree;
 pub mod list;
 pub mod rb_tree;
 pub mod rb_map;
+pub mod rb_multimap;
+pub mod rb_bag;
+pub mod graph;
+pub mod persistent_array;
+#[cfg(feature = "std")]
+pub mod interner;
+pub mod lazy_list;
+pub mod chunked_list;
 
 pub use list::List;
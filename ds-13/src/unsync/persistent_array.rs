@@ -0,0 +1,390 @@
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::mem;
+use crate::Error;
+
+/// A persistent array, with O(1) access to the most recently used
+/// version (Baker's trick).
+///
+/// Only one version owns the elements, every other version is a
+/// chain of diffs leading to it. Accessing an older version reroots
+/// the array, i.e. reverses the diffs on the path to that version,
+/// which takes time proportional to the length of the path.
+pub struct PersistentArray<T> {
+    node: Rc<RefCell<Data<T>>>,
+    len: usize,
+}
+
+enum Data<T> {
+    Array(Vec<T>),
+    /// The version equals the next version with the element at
+    /// the index replaced.
+    Diff(usize, T, Rc<RefCell<Data<T>>>),
+}
+
+impl<T: Clone> PersistentArray<T> {
+    /// Creates an array of `len` copies of `value`.
+    pub fn new(len: usize, value: T) -> Self {
+        PersistentArray::from_vec(vec_of(len, value))
+    }
+
+    pub fn from_vec(elements: Vec<T>) -> Self {
+        PersistentArray {
+            len: elements.len(),
+            node: Rc::new(RefCell::new(Data::Array(elements))),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the element at index `i`, or `None` if `i` is out
+    /// of bounds.
+    ///
+    /// Complexity: O(1) for the most recently used version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::persistent_array::PersistentArray;
+    ///
+    /// let a1 = PersistentArray::new(3, 0);
+    /// let a2 = a1.set(1, 5);
+    ///
+    /// assert_eq!(a2.get(1), Some(5));
+    /// assert_eq!(a1.get(1), Some(0));
+    /// assert_eq!(a1.get(3), None);
+    /// ```
+    pub fn get(&self, i: usize) -> Option<T> {
+        self.reroot();
+        match &*self.node.borrow() {
+            Data::Array(elements) => elements.get(i).cloned(),
+            Data::Diff(..) => unreachable!("rerooted array has diffs"),
+        }
+    }
+
+    /// Returns a new version with the element at index `i` set to `value`.
+    ///
+    /// Complexity: O(1) for the most recently used version.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `i` is out of bounds.
+    pub fn set(&self, i: usize, value: T) -> Self {
+        match self.try_set(i, value) {
+            Err(error) => panic!("{}", error),
+            Ok(array) => array,
+        }
+    }
+
+    /// Returns a new version with the element at index `i` set to
+    /// `value`, or `Error::IndexOutOfBounds` if `i` is out of bounds.
+    pub fn try_set(&self, i: usize, value: T) -> Result<Self, Error> {
+        if i >= self.len {
+            return Err(Error::IndexOutOfBounds { index: i, len: self.len });
+        }
+        self.reroot();
+        let mut data = self.node.borrow_mut();
+        let elements = match &mut *data {
+            Data::Array(elements) => elements,
+            Data::Diff(..) => unreachable!("rerooted array has diffs"),
+        };
+        let old = mem::replace(&mut elements[i], value);
+        let node = Rc::new(RefCell::new(Data::Array(mem::take(elements))));
+        *data = Data::Diff(i, old, Rc::clone(&node));
+        Ok(PersistentArray { node, len: self.len })
+    }
+
+    /// Returns the elements of this version.
+    pub fn to_vec(&self) -> Vec<T> {
+        self.reroot();
+        match &*self.node.borrow() {
+            Data::Array(elements) => elements.clone(),
+            Data::Diff(..) => unreachable!("rerooted array has diffs"),
+        }
+    }
+
+    /// Makes this version own the elements, by reversing the
+    /// diffs on the path to the version that owns them.
+    fn reroot(&self) {
+        let mut path = vec_of(1, Rc::clone(&self.node));
+        loop {
+            let next = match &*path[path.len() - 1].borrow() {
+                Data::Array(_) => break,
+                Data::Diff(_, _, next) => Rc::clone(next),
+            };
+            path.push(next);
+        }
+        for pair in path.windows(2).rev() {
+            let (node, next) = (&pair[0], &pair[1]);
+            let diff = mem::replace(&mut *node.borrow_mut(), Data::Array(Vec::new()));
+            let mut next_data = next.borrow_mut();
+            if let (Data::Diff(i, value, _), Data::Array(elements)) = (diff, &mut *next_data) {
+                let old = mem::replace(&mut elements[i], value);
+                *node.borrow_mut() = Data::Array(mem::take(elements));
+                *next_data = Data::Diff(i, old, Rc::clone(node));
+            }
+        }
+        // Versions that are only kept alive by `path` are dropped
+        // one at a time from the far end, not recursively.
+        while path.pop().is_some() {}
+    }
+}
+
+fn vec_of<T: Clone>(len: usize, value: T) -> Vec<T> {
+    let mut elements = Vec::with_capacity(len);
+    elements.resize(len, value);
+    elements
+}
+
+impl<T> Clone for PersistentArray<T> {
+    fn clone(&self) -> Self {
+        PersistentArray { node: Rc::clone(&self.node), len: self.len }
+    }
+}
+
+impl<T> Drop for PersistentArray<T> {
+    fn drop(&mut self) {
+        let mut data = match Rc::get_mut(&mut self.node) {
+            Some(cell) => mem::replace(cell.get_mut(), Data::Array(Vec::new())),
+            None => return,
+        };
+        while let Data::Diff(_, _, next) = data {
+            data = match Rc::try_unwrap(next) {
+                Ok(cell) => cell.into_inner(),
+                Err(_) => break,
+            };
+        }
+    }
+}
+
+/// A persistent union-find structure over the elements `0..len`,
+/// with union by rank and path compression.
+///
+/// Path compression updates the structure in place, which doesn't
+/// change the partition of any version.
+pub struct UnionFind {
+    parents: RefCell<PersistentArray<usize>>,
+    ranks: PersistentArray<usize>,
+}
+
+impl UnionFind {
+    /// Creates a union-find structure where every element is in
+    /// a set of its own.
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parents: RefCell::new(PersistentArray::from_vec((0..len).collect())),
+            ranks: PersistentArray::new(len, 0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+
+    /// Returns the representative of the set containing `x`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `x` is out of bounds.
+    pub fn find(&self, x: usize) -> usize {
+        match self.try_find(x) {
+            Err(error) => panic!("{}", error),
+            Ok(root) => root,
+        }
+    }
+
+    /// Returns the representative of the set containing `x`, or
+    /// `Error::IndexOutOfBounds` if `x` is out of bounds.
+    pub fn try_find(&self, x: usize) -> Result<usize, Error> {
+        let parents = self.parents.borrow().clone();
+        let mut path = Vec::new();
+        let mut root = x;
+        loop {
+            let parent = parents
+                .get(root)
+                .ok_or(Error::IndexOutOfBounds { index: x, len: self.len() })?;
+            if parent == root {
+                break;
+            }
+            path.push(root);
+            root = parent;
+        }
+        if path.len() > 1 {
+            let compressed = path.into_iter().fold(parents, |parents, y| parents.set(y, root));
+            *self.parents.borrow_mut() = compressed;
+        }
+        Ok(root)
+    }
+
+    /// Returns `true` if `x` and `y` are in the same set.
+    pub fn connected(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    /// Returns a new version where the sets containing `x` and `y`
+    /// are merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::persistent_array::UnionFind;
+    ///
+    /// let uf1 = UnionFind::new(4);
+    /// let uf2 = uf1.union(0, 1).union(2, 3);
+    /// let uf3 = uf2.union(1, 3);
+    ///
+    /// assert!(uf2.connected(0, 1));
+    /// assert!(!uf2.connected(0, 3));
+    /// assert!(uf3.connected(0, 3));
+    /// assert!(!uf1.connected(0, 1));
+    /// ```
+    pub fn union(&self, x: usize, y: usize) -> Self {
+        match self.try_union(x, y) {
+            Err(error) => panic!("{}", error),
+            Ok(uf) => uf,
+        }
+    }
+
+    /// Returns a new version where the sets containing `x` and `y`
+    /// are merged, or `Error::IndexOutOfBounds` if either is out of
+    /// bounds.
+    pub fn try_union(&self, x: usize, y: usize) -> Result<Self, Error> {
+        let (rx, ry) = (self.try_find(x)?, self.try_find(y)?);
+        let parents = self.parents.borrow().clone();
+        if rx == ry {
+            return Ok(UnionFind { parents: RefCell::new(parents), ranks: self.ranks.clone() });
+        }
+        let (rank_x, rank_y) = (self.ranks.get(rx).unwrap(), self.ranks.get(ry).unwrap());
+        Ok(if rank_x < rank_y {
+            UnionFind { parents: RefCell::new(parents.set(rx, ry)), ranks: self.ranks.clone() }
+        } else if rank_x > rank_y {
+            UnionFind { parents: RefCell::new(parents.set(ry, rx)), ranks: self.ranks.clone() }
+        } else {
+            UnionFind {
+                parents: RefCell::new(parents.set(ry, rx)),
+                ranks: self.ranks.set(rx, rank_x + 1),
+            }
+        })
+    }
+}
+
+impl Clone for UnionFind {
+    fn clone(&self) -> Self {
+        UnionFind {
+            parents: RefCell::new(self.parents.borrow().clone()),
+            ranks: self.ranks.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_creates_array() {
+        let a = PersistentArray::new(3, 'x');
+
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.to_vec(), vec!('x', 'x', 'x'));
+        assert!(PersistentArray::<i32>::from_vec(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn set_keeps_older_versions() {
+        let a0 = PersistentArray::from_vec(vec!(1, 2, 3));
+        let a1 = a0.set(0, 10);
+        let a2 = a1.set(2, 30);
+        let b1 = a0.set(1, 20);
+
+        assert_eq!(a2.to_vec(), vec!(10, 2, 30));
+        assert_eq!(a0.to_vec(), vec!(1, 2, 3));
+        assert_eq!(b1.to_vec(), vec!(1, 20, 3));
+        assert_eq!(a1.to_vec(), vec!(10, 2, 3));
+        assert_eq!(a2.get(2), Some(30));
+        assert_eq!(b1.get(1), Some(20));
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_out_of_bounds_panics() {
+        PersistentArray::new(2, 0).set(2, 1);
+    }
+
+    #[test]
+    fn try_set_out_of_bounds_returns_error() {
+        let a = PersistentArray::new(2, 0);
+
+        assert_eq!(a.try_set(1, 1).map(|a| a.to_vec()), Ok(vec!(0, 1)));
+        assert!(matches!(
+            a.try_set(2, 1),
+            Err(Error::IndexOutOfBounds { index: 2, len: 2 })
+        ));
+    }
+
+    #[test]
+    fn reroot_long_chain() {
+        let first = PersistentArray::new(4, 0usize);
+        let last = (0..100_000).fold(first.clone(), |a, n| a.set(n % 4, n));
+
+        assert_eq!(first.to_vec(), vec!(0, 0, 0, 0));
+        assert_eq!(last.to_vec(), vec!(99_996, 99_997, 99_998, 99_999));
+        drop(last);
+        assert_eq!(first.get(0), Some(0));
+    }
+
+    #[test]
+    fn drop_oldest_of_long_chain() {
+        let first = PersistentArray::new(1, 0usize);
+        let last = (0..100_000).fold(first.clone(), |a, n| a.set(0, n));
+        assert_eq!(first.get(0), Some(0));
+
+        drop(last);
+        drop(first);
+    }
+
+    #[test]
+    fn union_find_merges_sets() {
+        let uf = UnionFind::new(6).union(0, 1).union(1, 2).union(3, 4);
+
+        assert!(uf.connected(0, 2));
+        assert!(uf.connected(3, 4));
+        assert!(!uf.connected(2, 3));
+        assert_eq!(uf.find(5), 5);
+        assert_eq!(uf.len(), 6);
+    }
+
+    #[test]
+    fn union_find_versions_are_independent() {
+        let base = UnionFind::new(8);
+        let chain = (0..7).fold(base.clone(), |uf, x| uf.union(x, x + 1));
+        let pairs = base.union(0, 1).union(6, 7);
+
+        assert!(chain.connected(0, 7));
+        assert!(!pairs.connected(0, 7));
+        assert!(pairs.connected(6, 7));
+        assert!(!base.connected(0, 1));
+        assert!((0..8).all(|x| chain.find(x) == chain.find(0)));
+    }
+
+    #[test]
+    fn union_find_try_variants_check_bounds() {
+        let uf = UnionFind::new(3);
+
+        assert_eq!(uf.try_find(2), Ok(2));
+        assert_eq!(uf.try_find(3), Err(Error::IndexOutOfBounds { index: 3, len: 3 }));
+        assert!(uf.try_union(0, 3).is_err());
+        assert!(uf.try_union(0, 2).unwrap().connected(0, 2));
+    }
+}
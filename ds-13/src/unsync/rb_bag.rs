@@ -0,0 +1,293 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use crate::unsync::rb_map::{self, RBMap};
+use crate::stats::{Measure, NodeVisitor};
+
+/// A persistent multiset, that stores every distinct element once
+/// together with its number of occurrences.
+pub struct RBBag<T> {
+    counts: RBMap<T, usize>,
+    len: usize,
+}
+
+impl<T> RBBag<T>
+where
+    T: Clone + PartialOrd,
+{
+    pub fn new() -> Self {
+        RBBag { counts: RBMap::new(), len: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements, counting every occurrence.
+    ///
+    /// Complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of occurrences of `x`.
+    ///
+    /// Complexity: O(log n)
+    pub fn count(&self, x: &T) -> usize {
+        self.counts.get(x).copied().unwrap_or(0)
+    }
+
+    pub fn contains(&self, x: &T) -> bool {
+        self.counts.contains_key(x)
+    }
+
+    /// Returns a bag with one more occurrence of `x`.
+    ///
+    /// Complexity: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::rb_bag::RBBag;
+    ///
+    /// let b1 = RBBag::new().inserted("NP").inserted("VP");
+    /// let b2 = b1.inserted("NP");
+    ///
+    /// assert_eq!(b1.count(&"NP"), 1);
+    /// assert_eq!(b2.count(&"NP"), 2);
+    /// assert_eq!(b2.len(), 3);
+    /// ```
+    pub fn inserted(&self, x: T) -> Self {
+        self.inserted_n(x, 1)
+    }
+
+    /// Returns a bag with `n` more occurrences of `x`.
+    ///
+    /// Complexity: O(log n)
+    pub fn inserted_n(&self, x: T, n: usize) -> Self {
+        if n == 0 {
+            return self.clone();
+        }
+        RBBag {
+            counts: self.counts.updated(x, |count| count.map_or(n, |count| count + n)),
+            len: self.len + n,
+        }
+    }
+
+    /// Returns a bag with one occurrence of `x` less, or a clone of
+    /// `self` if `x` doesn't occur.
+    ///
+    /// Complexity: O(log n)
+    pub fn removed_one(&self, x: &T) -> Self {
+        match self.count(x) {
+            0 => self.clone(),
+            1 => RBBag { counts: self.counts.removed(x), len: self.len - 1 },
+            n => RBBag {
+                counts: self.counts.inserted_or_replaced(x.clone(), n - 1),
+                len: self.len - 1,
+            },
+        }
+    }
+
+    /// Returns a bag where every element occurs as often as in the
+    /// bag where it occurs most often.
+    ///
+    /// Complexity: O(m log(n + m))
+    pub fn union(&self, other: &Self) -> Self {
+        other.iter_with_counts().fold(self.clone(), |bag, (x, n)| {
+            let count = bag.count(x);
+            if n > count {
+                bag.inserted_n(x.clone(), n - count)
+            } else {
+                bag
+            }
+        })
+    }
+
+    /// Returns a bag where every element occurs as often as in the
+    /// bag where it occurs least often.
+    ///
+    /// Complexity: O(n log m)
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.iter_with_counts().fold(RBBag::new(), |bag, (x, n)| {
+            bag.inserted_n(x.clone(), n.min(other.count(x)))
+        })
+    }
+
+    /// Returns a bag with the occurrences of both bags.
+    ///
+    /// Complexity: O(m log(n + m))
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::rb_bag::RBBag;
+    ///
+    /// let b1: RBBag<char> = "aab".chars().collect();
+    /// let b2: RBBag<char> = "abc".chars().collect();
+    ///
+    /// assert_eq!(b1.sum(&b2).count(&'a'), 3);
+    /// assert_eq!(b1.union(&b2).count(&'a'), 2);
+    /// assert_eq!(b1.intersection(&b2).count(&'a'), 1);
+    /// assert!(!b1.intersection(&b2).contains(&'c'));
+    /// ```
+    pub fn sum(&self, other: &Self) -> Self {
+        other
+            .iter_with_counts()
+            .fold(self.clone(), |bag, (x, n)| bag.inserted_n(x.clone(), n))
+    }
+}
+
+impl<T> RBBag<T> {
+    /// Returns an iterator over the distinct elements and their
+    /// number of occurrences, in ascending order.
+    pub fn iter_with_counts(&self) -> IterWithCounts<'_, T> {
+        IterWithCounts(self.counts.iter())
+    }
+
+    /// Returns an iterator over every occurrence of the elements,
+    /// in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.iter_with_counts()
+            .flat_map(|(x, n)| core::iter::repeat_n(x, n))
+    }
+}
+
+pub struct IterWithCounts<'a, T>(rb_map::Iter<'a, T, usize>);
+
+impl<'a, T> Iterator for IterWithCounts<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(x, n)| (x, *n))
+    }
+}
+
+impl<T> Clone for RBBag<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        RBBag { counts: self.counts.clone(), len: self.len }
+    }
+}
+
+impl<T> Default for RBBag<T>
+where
+    T: Clone + PartialOrd,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for RBBag<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter_with_counts()).finish()
+    }
+}
+
+impl<T> PartialEq for RBBag<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter_with_counts().eq(other.iter_with_counts())
+    }
+}
+
+impl<T: Eq> Eq for RBBag<T> {}
+
+impl<T: Hash> Hash for RBBag<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.counts.hash(state);
+    }
+}
+
+impl<T> FromIterator<T> for RBBag<T>
+where
+    T: Clone + PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().fold(RBBag::new(), |bag, x| bag.inserted(x))
+    }
+}
+
+impl<T> Measure for RBBag<T> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        self.counts.visit_nodes(visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bag(s: &str) -> RBBag<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn new_creates_empty_bag() {
+        let b = RBBag::<i32>::new();
+
+        assert!(b.is_empty());
+        assert_eq!(b.len(), 0);
+        assert_eq!(b.count(&1), 0);
+    }
+
+    #[test]
+    fn inserted_keeps_older_versions() {
+        let b1 = RBBag::new().inserted(1);
+        let b2 = b1.inserted(1).inserted(2);
+
+        assert_eq!(b1.count(&1), 1);
+        assert_eq!(b2.count(&1), 2);
+        assert_eq!(b2.len(), 3);
+        assert!(!b1.contains(&2));
+    }
+
+    #[test]
+    fn removed_one_removes_one_occurrence() {
+        let b = bag("aab");
+
+        let b1 = b.removed_one(&'a');
+        let b2 = b1.removed_one(&'a');
+
+        assert_eq!(b1.count(&'a'), 1);
+        assert!(!b2.contains(&'a'));
+        assert_eq!(b2.len(), 1);
+        assert_eq!(b2.removed_one(&'a'), b2);
+    }
+
+    #[test]
+    fn iter_with_counts_is_ordered() {
+        let b = bag("cabca");
+
+        assert_eq!(
+            b.iter_with_counts().collect::<Vec<_>>(),
+            vec!((&'a', 2), (&'b', 1), (&'c', 2))
+        );
+        assert_eq!(b.iter().collect::<String>(), "aabcc");
+    }
+
+    #[test]
+    fn multiset_operations() {
+        let b1 = bag("aabx");
+        let b2 = bag("abbby");
+
+        assert_eq!(b1.union(&b2), bag("aabbbxy"));
+        assert_eq!(b1.intersection(&b2), bag("ab"));
+        assert_eq!(b1.sum(&b2), bag("aaabbbbxy"));
+        assert_eq!(b1.sum(&b2).len(), b1.len() + b2.len());
+    }
+
+    #[test]
+    fn equality_ignores_insertion_order() {
+        assert_eq!(bag("abc"), bag("cba"));
+        assert!(bag("abc") != bag("abcc"));
+    }
+}
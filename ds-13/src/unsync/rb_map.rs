@@ -1,5 +1,12 @@
-use std::cmp::Ordering;
-use crate::unsync::rb_tree::{RBTree};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use crate::unsync::rb_tree::{self, RBTree};
+use crate::stats::{Measure, NodeVisitor};
+use crate::traits::{Filterable, Foldable, Functor};
 
 #[derive(Debug)]
 pub struct KeyValue<K, V>(K, V);
@@ -91,6 +98,36 @@ where
         )
     }
 
+    /// Returns a map without the entry for `k`.
+    ///
+    /// Complexity: O(log n)
+    pub fn removed(&self, k: &K) -> Self {
+        RBMap( self.0.removed(k) )
+    }
+
+    /// Sets the value for `k` to `f(Some(value))`, or to `f(None)` if
+    /// there is no entry for `k`, in a single descent.
+    ///
+    /// Complexity: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::rb_map::RBMap;
+    ///
+    /// let m = RBMap::new().inserted("a", 1);
+    /// let increment = |v: Option<&i32>| v.map_or(1, |v| v + 1);
+    ///
+    /// assert_eq!(m.updated("a", increment).get(&"a"), Some(&2));
+    /// assert_eq!(m.updated("b", increment).get(&"b"), Some(&1));
+    /// ```
+    pub fn updated(&self, k: K, f: impl FnOnce(Option<&V>) -> V) -> Self {
+        let key = k.clone();
+        RBMap(
+            self.0.updated(&key, |kv| KeyValue(k, f(kv.map(|kv| &kv.1))))
+        )
+    }
+
     pub fn get(&self, k: &K) -> Option<&V> {
         match self.0.get(k) {
             None => None,
@@ -113,6 +150,32 @@ where
     }
 }
 
+impl<K, V> RBMap<K, V> {
+    /// Returns an iterator over the entries in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.0.iter())
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a RBMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, K, V>(rb_tree::Iter<'a, KeyValue<K, V>>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|kv| (&kv.0, &kv.1))
+    }
+}
+
 impl<K, V> Clone for RBMap<K, V>
 where
     K: Clone,
@@ -123,6 +186,144 @@ where
     }
 }
 
+impl<K, V> Default for RBMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for RBMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    /// Creates a map with the entries, later values replacing earlier
+    /// values for the same key.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter().fold(RBMap::new(), |map, (k, v)| map.inserted_or_replaced(k, v))
+    }
+}
+
+impl<K, V> From<Vec<(K, V)>> for RBMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    fn from(entries: Vec<(K, V)>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<K, V, const N: usize> From<[(K, V); N]> for RBMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    fn from(entries: [(K, V); N]) -> Self {
+        IntoIterator::into_iter(entries).collect()
+    }
+}
+
+impl<K, V> From<BTreeMap<K, V>> for RBMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    fn from(entries: BTreeMap<K, V>) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<K, V> fmt::Debug for RBMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> PartialEq for RBMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for RBMap<K, V> {}
+
+impl<K, V> PartialOrd for RBMap<K, V>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    /// Compares the entries in ascending key order lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Ord, V: Ord> Ord for RBMap<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K: Hash, V: Hash> Hash for RBMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for entry in self {
+            entry.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
+    }
+}
+
+impl<K, V> Foldable for RBMap<K, V> {
+    type Item = V;
+
+    fn foldl<U>(&self, mut f: impl FnMut(U, &V) -> U, acc: U) -> U {
+        self.0.foldl(|acc, kv| f(acc, &kv.1), acc)
+    }
+
+    fn foldr<U>(&self, mut f: impl FnMut(&V, U) -> U, acc: U) -> U {
+        self.0.foldr(|kv, acc| f(&kv.1, acc), acc)
+    }
+}
+
+impl<K: Clone, V> Functor for RBMap<K, V> {
+    type Mapped<U> = RBMap<K, U>;
+
+    fn fmap<U>(&self, mut f: impl FnMut(&V) -> U) -> RBMap<K, U> {
+        RBMap(self.0.map_shape(|kv| KeyValue(kv.0.clone(), f(&kv.1))))
+    }
+}
+
+impl<K, V> Filterable for RBMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    fn filter(&self, mut p: impl FnMut(&V) -> bool) -> Self {
+        RBMap(Filterable::filter(&self.0, |kv| p(&kv.1)))
+    }
+}
+
+impl<K, V> Measure for RBMap<K, V> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        self.0.visit_nodes(visitor);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +361,52 @@ mod tests {
         assert_eq!(m1.get_or_default(&"g", &0), &0);
         assert_eq!(m.get_or_default(&"g", &0), &5);
     }
+
+    #[test]
+    fn removed_returns_smaller_map() {
+        let m1 = RBMap::new().inserted(1, "a").inserted(2, "b");
+
+        let m2 = m1.removed(&1);
+
+        assert!(m1.contains_key(&1));
+        assert!(!m2.contains_key(&1));
+        assert_eq!(m2.get(&2), Some(&"b"));
+        assert!(m2.removed(&2).is_empty());
+    }
+
+    #[test]
+    fn iter_returns_entries_in_key_order() {
+        let m = RBMap::new()
+            .inserted(2, "b")
+            .inserted(3, "c")
+            .inserted(1, "a");
+
+        assert_eq!(
+            m.iter().collect::<Vec<_>>(),
+            vec!((&1, &"a"), (&2, &"b"), (&3, &"c"))
+        );
+    }
+
+    #[test]
+    fn from_conversions() {
+        let btree: BTreeMap<i32, &str> = vec!((2, "b"), (1, "a")).into_iter().collect();
+
+        let m1 = RBMap::from(btree);
+        let m2 = RBMap::from([(1, "x"), (2, "b"), (1, "a")]);
+
+        assert_eq!(m1, m2);
+        assert_eq!(m2.get(&1), Some(&"a"));
+        assert_eq!(RBMap::from(vec!((1, "a"))), RBMap::new().inserted(1, "a"));
+        assert_eq!(RBMap::<i32, i32>::default(), RBMap::new());
+    }
+
+    #[test]
+    fn equality_and_ordering_use_values() {
+        let m = RBMap::from([(1, 'a'), (2, 'b')]);
+
+        assert!(m != RBMap::from([(1, 'a'), (2, 'c')]));
+        assert!(m < RBMap::from([(1, 'a'), (2, 'c')]));
+        assert!(m > RBMap::from([(1, 'a')]));
+        assert_eq!(format!("{:?}", m), "{1: 'a', 2: 'b'}");
+    }
 } // mod tests
@@ -0,0 +1,260 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use crate::unsync::list::{self, List};
+use crate::unsync::rb_map::{self, RBMap};
+use crate::stats::{Measure, NodeVisitor};
+
+/// A persistent map from keys to one or more values.
+///
+/// The values for a key are kept in a `List`, most recently pushed
+/// first, with their number, and a key without values is removed from
+/// the map.
+pub struct RBMultiMap<K, V>(RBMap<K, Values<V>>);
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Values<V> {
+    len: usize,
+    list: List<V>,
+}
+
+impl<K, V> RBMultiMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        RBMultiMap( RBMap::new() )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains_key(&self, k: &K) -> bool {
+        self.0.contains_key(k)
+    }
+
+    /// Returns a map with `v` added in front of the values for `k`.
+    ///
+    /// Complexity: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::rb_multimap::RBMultiMap;
+    ///
+    /// let m = RBMultiMap::new().pushed("a", 1).pushed("a", 2).pushed("b", 3);
+    ///
+    /// assert_eq!(m.get_all(&"a").collect::<Vec<_>>(), vec!(&2, &1));
+    /// assert_eq!(m.count(&"b"), 1);
+    /// assert_eq!(m.count(&"c"), 0);
+    /// ```
+    pub fn pushed(&self, k: K, v: V) -> Self {
+        RBMultiMap(
+            self.0.updated(k, |values| match values {
+                None => Values { len: 1, list: List::from_value(v) },
+                Some(values) => Values { len: values.len + 1, list: values.list.pushed_front(v) },
+            })
+        )
+    }
+
+    /// Returns the values for `k`, most recently pushed first,
+    /// or `None` if there are none.
+    pub fn get(&self, k: &K) -> Option<&List<V>> {
+        self.0.get(k).map(|values| &values.list)
+    }
+
+    /// Returns an iterator over the values for `k`, most recently
+    /// pushed first.
+    pub fn get_all(&self, k: &K) -> GetAll<'_, V> {
+        GetAll(self.get(k).map(List::iter))
+    }
+
+    /// Returns the number of values for `k`.
+    ///
+    /// Complexity: O(log n)
+    pub fn count(&self, k: &K) -> usize {
+        self.0.get(k).map_or(0, |values| values.len)
+    }
+
+    /// Returns a map without the most recently pushed value for `k`.
+    ///
+    /// Complexity: O(log n)
+    pub fn removed_one(&self, k: &K) -> Self {
+        match self.0.get(k) {
+            None => self.clone(),
+            Some(values) => {
+                let rest = Values { len: values.len - 1, list: values.list.popped_front() };
+                if rest.list.is_empty() {
+                    self.removed_all(k)
+                } else {
+                    RBMultiMap( self.0.inserted_or_replaced(k.clone(), rest) )
+                }
+            }
+        }
+    }
+
+    /// Returns a map without any values for `k`.
+    ///
+    /// Complexity: O(log n)
+    pub fn removed_all(&self, k: &K) -> Self {
+        RBMultiMap( self.0.removed(k) )
+    }
+}
+
+impl<K, V> RBMultiMap<K, V> {
+    /// Returns an iterator over the keys and their values,
+    /// in ascending key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.0.iter())
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a RBMultiMap<K, V> {
+    type Item = (&'a K, &'a List<V>);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, K, V>(rb_map::Iter<'a, K, Values<V>>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a List<V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(k, values)| (k, &values.list))
+    }
+}
+
+pub struct GetAll<'a, V>(Option<list::Iter<'a, V>>);
+
+impl<'a, V> Iterator for GetAll<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.as_mut().and_then(Iterator::next)
+    }
+}
+
+impl<K, V> Clone for RBMultiMap<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        RBMultiMap(self.0.clone())
+    }
+}
+
+impl<K, V> Default for RBMultiMap<K, V>
+where
+    K: Clone + PartialOrd,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> fmt::Debug for RBMultiMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> PartialEq for RBMultiMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for RBMultiMap<K, V> {}
+
+impl<K: Hash, V: Hash> Hash for RBMultiMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<K, V> Measure for RBMultiMap<K, V> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        self.0.visit_nodes(visitor);
+        for (_k, values) in self.iter() {
+            values.visit_nodes(visitor);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::measure;
+
+    #[test]
+    fn new_creates_empty_map() {
+        let m = RBMultiMap::<i32, &str>::new();
+
+        assert!(m.is_empty());
+        assert_eq!(m.count(&1), 0);
+        assert_eq!(m.get_all(&1).next(), None);
+    }
+
+    #[test]
+    fn pushed_keeps_older_versions() {
+        let m1 = RBMultiMap::new().pushed(1, "a");
+        let m2 = m1.pushed(1, "b").pushed(2, "c");
+
+        assert_eq!(m1.get_all(&1).collect::<Vec<_>>(), vec!(&"a"));
+        assert_eq!(m2.get_all(&1).collect::<Vec<_>>(), vec!(&"b", &"a"));
+        assert_eq!(m2.count(&2), 1);
+        assert!(m2.contains_key(&2));
+        assert!(!m1.contains_key(&2));
+    }
+
+    #[test]
+    fn pushed_shares_older_values() {
+        let m1 = RBMultiMap::new().pushed(1, 10).pushed(1, 11);
+        let m2 = m1.pushed(1, 12);
+
+        assert!(m2.get(&1).unwrap().popped_front().ptr_eq(m1.get(&1).unwrap()));
+        assert_eq!(measure(&[&m1, &m2]).nodes, 5);
+    }
+
+    #[test]
+    fn removed_one_removes_latest_value() {
+        let m = RBMultiMap::new().pushed("k", 1).pushed("k", 2);
+
+        let m1 = m.removed_one(&"k");
+        let m2 = m1.removed_one(&"k");
+
+        assert_eq!(m1.get_all(&"k").collect::<Vec<_>>(), vec!(&1));
+        assert_eq!(m1.count(&"k"), 1);
+        assert!(!m2.contains_key(&"k"));
+        assert!(m2.is_empty());
+        assert!(m2.removed_one(&"k").is_empty());
+    }
+
+    #[test]
+    fn removed_all_removes_key() {
+        let m = RBMultiMap::new().pushed(1, 'a').pushed(1, 'b').pushed(2, 'c');
+
+        let m = m.removed_all(&1);
+
+        assert_eq!(m.count(&1), 0);
+        assert_eq!(
+            m.iter().map(|(k, vs)| (*k, vs.len())).collect::<Vec<_>>(),
+            vec!((2, 1))
+        );
+    }
+}
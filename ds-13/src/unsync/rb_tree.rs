@@ -1,10 +1,20 @@
-use std::rc::Rc;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use crate::stats::{Measure, NodeVisitor};
+use crate::traits::{Filterable, Foldable};
+use crate::Error;
 
-#[derive(Debug)]
 pub struct RBTree<T> {
     root: Link<T>,
 }
 
+/// A persistent set, i.e. an `RBTree` without duplicate elements.
+pub type RBSet<T> = RBTree<T>;
+
 type Link<T> = Option<Rc<RBNode<T>>>;
 
 #[derive(Debug)]
@@ -88,6 +98,24 @@ impl<T: Clone> RBTree<T> {
         Self { root: self.root.as_ref().and_then(|node| node.right.clone()) } 
     }
 
+    /// Returns the left subtree, or `Error::EmptyTree` if the tree
+    /// is empty.
+    pub fn try_left(&self) -> Result<Self, Error> {
+        match &self.root {
+            None => Err(Error::EmptyTree),
+            Some(node) => Ok(Self { root: node.left.clone() }),
+        }
+    }
+
+    /// Returns the right subtree, or `Error::EmptyTree` if the tree
+    /// is empty.
+    pub fn try_right(&self) -> Result<Self, Error> {
+        match &self.root {
+            None => Err(Error::EmptyTree),
+            Some(node) => Ok(Self { root: node.right.clone() }),
+        }
+    }
+
     pub fn inserted(&self, x: T) -> Self 
     where
         T: PartialOrd,
@@ -148,6 +176,46 @@ impl<T: Clone> RBTree<T> {
         }
     }
 
+    /// Returns a tree without the element equal to `x`, or a tree
+    /// sharing every node with `self` if there is none.
+    ///
+    /// Complexity: O(log n)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ds_13::unsync::rb_tree::RBTree;
+    ///
+    /// let t1 = RBTree::new().inserted(1).inserted(2).inserted(3);
+    /// let t2 = t1.removed(&2);
+    ///
+    /// assert!(t1.contains(&2));
+    /// assert!(!t2.contains(&2));
+    /// assert!(t2.contains(&3));
+    /// ```
+    pub fn removed<U>(&self, x: &U) -> Self
+    where
+        T: PartialOrd<U>,
+    {
+        if !self.contains(x) {
+            return self.clone();
+        }
+        RBTree { root: link_removed(&self.root, x) }
+    }
+
+    /// Replaces the element equal to `x` with `f(Some(element))`, or
+    /// inserts `f(None)` if there is none, in a single descent.
+    ///
+    /// The element returned by `f` must be equal to `x`.
+    ///
+    /// Complexity: O(log n)
+    pub fn updated<U>(&self, x: &U, f: impl FnOnce(Option<&T>) -> T) -> Self
+    where
+        T: PartialOrd<U>,
+    {
+        RBTree { root: link_updated(&self.root, x, f) }
+    }
+
     fn ins(&self, x: T) -> Self
     where
         T: PartialOrd,
@@ -426,6 +494,210 @@ where
     }
 }
 
+fn link_updated<T, U>(link: &Link<T>, x: &U, f: impl FnOnce(Option<&T>) -> T) -> Link<T>
+where
+    T: Clone + PartialOrd<U>,
+{
+    let new_link = sorted_update(link, x, f);
+    paint_link(&new_link, Colour::Black)
+}
+
+fn sorted_update<T, U>(link: &Link<T>, x: &U, f: impl FnOnce(Option<&T>) -> T) -> Link<T>
+where
+    T: Clone + PartialOrd<U>,
+{
+    match link {
+        None => make_leaf_link(f(None)),
+        Some(node) => {
+            if &node.element > x {
+                balance_link(
+                    node.colour,
+                    node.element.clone(),
+                    sorted_update(&node.left, x, f),
+                    node.right.clone()
+                )
+            } else if &node.element < x {
+                balance_link(
+                    node.colour,
+                    node.element.clone(),
+                    node.left.clone(),
+                    sorted_update(&node.right, x, f)
+                )
+            } else {
+                make_link(
+                    node.colour,
+                    f(Some(&node.element)),
+                    node.left.clone(),
+                    node.right.clone()
+                )
+            }
+        }
+    }
+}
+
+// Deletion follows Kahrs, "Red-black trees with types" (2001).
+
+fn link_removed<T, U>(link: &Link<T>, x: &U) -> Link<T>
+where
+    T: Clone + PartialOrd<U>,
+{
+    let new_link = sorted_remove(link, x);
+    paint_link(&new_link, Colour::Black)
+}
+
+fn sorted_remove<T, U>(link: &Link<T>, x: &U) -> Link<T>
+where
+    T: Clone + PartialOrd<U>,
+{
+    use Colour::*;
+    match link {
+        None => None,
+        Some(node) => {
+            if &node.element > x {
+                if is_black(&node.left) {
+                    balance_left(sorted_remove(&node.left, x), node.element.clone(), node.right.clone())
+                } else {
+                    make_link(Red, node.element.clone(), sorted_remove(&node.left, x), node.right.clone())
+                }
+            } else if &node.element < x {
+                if is_black(&node.right) {
+                    balance_right(node.left.clone(), node.element.clone(), sorted_remove(&node.right, x))
+                } else {
+                    make_link(Red, node.element.clone(), node.left.clone(), sorted_remove(&node.right, x))
+                }
+            } else {
+                fuse(&node.left, &node.right)
+            }
+        }
+    }
+}
+
+fn is_black<T>(link: &Link<T>) -> bool {
+    link.as_ref().is_some_and(|node| node.colour == Colour::Black)
+}
+
+fn is_red<T>(link: &Link<T>) -> bool {
+    link.as_ref().is_some_and(|node| node.colour == Colour::Red)
+}
+
+/// Rebalances a node whose left subtree has lost one black node.
+fn balance_left<T: Clone>(left: Link<T>, x: T, right: Link<T>) -> Link<T> {
+    use Colour::*;
+    if is_red(&left) {
+        return make_link(Red, x, paint_link(&left, Black), right);
+    }
+    match &right {
+        Some(node) if node.colour == Black => {
+            balance_removed(left, x, paint_link(&right, Red))
+        },
+        Some(node) if node.colour == Red && is_black(&node.left) => {
+            let node_left = node.left.as_ref().unwrap();
+            make_link(
+                Red,
+                node_left.element.clone(),
+                make_link(Black, x, left, node_left.left.clone()),
+                balance_removed(
+                    node_left.right.clone(),
+                    node.element.clone(),
+                    paint_link(&node.right, Red)
+                )
+            )
+        },
+        // The right sibling of a subtree that lost a black node has a
+        // black height of at least one, so it exists, and if it's red
+        // its children are black.
+        _ => unreachable!("the right sibling has a black height of at least one"),
+    }
+}
+
+/// Rebalances a node whose right subtree has lost one black node.
+fn balance_right<T: Clone>(left: Link<T>, x: T, right: Link<T>) -> Link<T> {
+    use Colour::*;
+    if is_red(&right) {
+        return make_link(Red, x, left, paint_link(&right, Black));
+    }
+    match &left {
+        Some(node) if node.colour == Black => {
+            balance_removed(paint_link(&left, Red), x, right)
+        },
+        Some(node) if node.colour == Red && is_black(&node.right) => {
+            let node_right = node.right.as_ref().unwrap();
+            make_link(
+                Red,
+                node_right.element.clone(),
+                balance_removed(
+                    paint_link(&node.left, Red),
+                    node.element.clone(),
+                    node_right.left.clone()
+                ),
+                make_link(Black, x, node_right.right.clone(), right)
+            )
+        },
+        // As in `balance_left`, the left sibling exists, and if it's red
+        // its children are black.
+        _ => unreachable!("the left sibling has a black height of at least one"),
+    }
+}
+
+/// Like `balance_link` for a black node, but also turns a node with
+/// two red children into a red node with two black children.
+fn balance_removed<T: Clone>(left: Link<T>, x: T, right: Link<T>) -> Link<T> {
+    use Colour::*;
+    if is_red(&left) && is_red(&right) {
+        make_link(Red, x, paint_link(&left, Black), paint_link(&right, Black))
+    } else {
+        balance_link(Black, x, left, right)
+    }
+}
+
+/// Joins two trees of the same black height, where every element
+/// of `left` is less than every element of `right`.
+fn fuse<T: Clone>(left: &Link<T>, right: &Link<T>) -> Link<T> {
+    use Colour::*;
+    let (l, r) = match (left, right) {
+        (None, _) => return right.clone(),
+        (_, None) => return left.clone(),
+        (Some(l), Some(r)) => (l, r),
+    };
+    match (l.colour, r.colour) {
+        (Red, Red) => {
+            let middle = fuse(&l.right, &r.left);
+            match &middle {
+                Some(m) if m.colour == Red => make_link(
+                    Red,
+                    m.element.clone(),
+                    make_link(Red, l.element.clone(), l.left.clone(), m.left.clone()),
+                    make_link(Red, r.element.clone(), m.right.clone(), r.right.clone())
+                ),
+                _ => make_link(
+                    Red,
+                    l.element.clone(),
+                    l.left.clone(),
+                    make_link(Red, r.element.clone(), middle, r.right.clone())
+                ),
+            }
+        },
+        (Black, Black) => {
+            let middle = fuse(&l.right, &r.left);
+            match &middle {
+                Some(m) if m.colour == Red => make_link(
+                    Red,
+                    m.element.clone(),
+                    make_link(Black, l.element.clone(), l.left.clone(), m.left.clone()),
+                    make_link(Black, r.element.clone(), m.right.clone(), r.right.clone())
+                ),
+                _ => balance_left(
+                    l.left.clone(),
+                    l.element.clone(),
+                    make_link(Black, r.element.clone(), middle, r.right.clone())
+                ),
+            }
+        },
+        (Black, Red) => make_link(Red, r.element.clone(), fuse(left, &r.left), r.right.clone()),
+        (Red, Black) => make_link(Red, l.element.clone(), l.left.clone(), fuse(&l.right, right)),
+    }
+}
+
 //fn make_empty_node<T>() -> Rc<RBNode<T>> {
 //    Rc::new(RBNode::Empty)
 //}
@@ -551,31 +823,185 @@ where
     )
 }
 
-impl<T: PartialEq + Clone> PartialEq for RBTree<T> {
-    fn eq(&self, other: &Self) -> bool {
-        if self.is_empty() && other.is_empty() {
-            return true;
-        } else if self.is_empty() || other.is_empty() {
-            return false;
-        }
-        
-        if self.root() != other.root() {
-            return false;
+impl<T> RBTree<T> {
+    /// Returns an iterator over the elements in ascending order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// Creates a tree of the same shape with every element mapped by `f`.
+    ///
+    /// The result is only ordered if `f` is monotone.
+    pub(crate) fn map_shape<U>(&self, mut f: impl FnMut(&T) -> U) -> RBTree<U> {
+        RBTree { root: map_link(&self.root, &mut f) }
+    }
+}
+
+fn map_link<T, U>(link: &Link<T>, f: &mut impl FnMut(&T) -> U) -> Link<U> {
+    link.as_ref().map(|node| {
+        let left = map_link(&node.left, f);
+        let element = f(&node.element);
+        let right = map_link(&node.right, f);
+        Rc::new(RBNode { colour: node.colour, element, left, right })
+    })
+}
+
+impl<'a, T> IntoIterator for &'a RBTree<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<&'a RBNode<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn empty() -> Self {
+        Iter { stack: Vec::new() }
+    }
+
+    fn push_left(&mut self, mut link: &'a Link<T>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
         }
-        if self.left() != other.left() {
-            return false;
-        } 
-        if self.right() != other.right() {
-            return false;
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stack.pop().map(|node| {
+            self.push_left(&node.right);
+            &node.element
+        })
+    }
+}
+
+impl<T> Foldable for RBTree<T> {
+    type Item = T;
+
+    fn foldl<U>(&self, mut f: impl FnMut(U, &T) -> U, acc: U) -> U {
+        self.iter().fold(acc, &mut f)
+    }
+
+    fn foldr<U>(&self, mut f: impl FnMut(&T, U) -> U, acc: U) -> U {
+        let elements: Vec<&T> = self.iter().collect();
+        elements.into_iter().rev().fold(acc, |acc, x| f(x, acc))
+    }
+}
+
+impl<T: Clone + PartialOrd> Filterable for RBTree<T> {
+    fn filter(&self, mut p: impl FnMut(&T) -> bool) -> Self {
+        self.foldl(
+            |acc: RBTree<T>, x| if p(x) { acc.inserted(x.clone()) } else { acc },
+            RBTree::new(),
+        )
+    }
+}
+
+impl<T> Measure for RBTree<T> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        visit_link(&self.root, visitor);
+    }
+}
+
+fn visit_link<T>(link: &Link<T>, visitor: &mut NodeVisitor) {
+    if let Some(node) = link {
+        if visitor.visit(node) {
+            visit_link(&node.left, visitor);
+            visit_link(&node.right, visitor);
         }
+    }
+}
+
+impl<T: Clone> Default for RBTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for RBTree<T>
+where
+    T: Clone + PartialOrd,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().fold(RBTree::new(), |tree, x| tree.inserted(x))
+    }
+}
+
+impl<T> From<Vec<T>> for RBTree<T>
+where
+    T: Clone + PartialOrd,
+{
+    fn from(elements: Vec<T>) -> Self {
+        elements.into_iter().collect()
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for RBTree<T>
+where
+    T: Clone + PartialOrd,
+{
+    fn from(elements: [T; N]) -> Self {
+        IntoIterator::into_iter(elements).collect()
+    }
+}
 
-        true
+impl<T> fmt::Debug for RBTree<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
     }
 }
 
-impl<T: PartialEq> PartialEq for RBNode<T> {
+/// Trees are equal if they have the same elements, whatever their shape.
+impl<T: PartialEq> PartialEq for RBTree<T> {
     fn eq(&self, other: &Self) -> bool {
-        false
+        let same_root = match (&self.root, &other.root) {
+            (Some(x), Some(y)) => Rc::ptr_eq(x, y),
+            (None, None) => true,
+            _ => false,
+        };
+        same_root || self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for RBTree<T> {}
+
+impl<T> PartialOrd for RBTree<T>
+where
+    T: PartialOrd,
+{
+    /// Compares the ascending sequences of elements lexicographically.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for RBTree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for RBTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for x in self {
+            x.hash(state);
+            len += 1;
+        }
+        state.write_usize(len);
     }
 }
 
@@ -583,6 +1009,16 @@ impl<T: PartialEq> PartialEq for RBNode<T> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn try_left_right_return_error_on_empty_tree() {
+        let t = RBTree::new().inserted(2).inserted(1).inserted(3);
+
+        assert_eq!(t.try_left().ok().and_then(|l| l.root().copied()), Some(1));
+        assert_eq!(t.try_right().ok().and_then(|r| r.root().copied()), Some(3));
+        assert_eq!(RBTree::<i32>::new().try_left().err(), Some(Error::EmptyTree));
+        assert_eq!(RBTree::<i32>::new().try_right().err(), Some(Error::EmptyTree));
+    }
+
     #[test]
     fn new_creates_empty_tree() {
         let tree = RBTree::<i32>::new();
@@ -701,4 +1137,105 @@ mod tests {
 
         assert_eq!(t1.get_or_default(&5, &7), &7);
     }
+
+    /// Returns the black height, checking the red-black invariants.
+    fn black_height<T: PartialOrd>(link: &Link<T>) -> usize {
+        match link {
+            None => 1,
+            Some(node) => {
+                if node.colour == Colour::Red {
+                    assert!(!is_red(&node.left) && !is_red(&node.right));
+                }
+                let left = black_height(&node.left);
+                assert_eq!(left, black_height(&node.right));
+                left + (node.colour == Colour::Black) as usize
+            }
+        }
+    }
+
+    #[test]
+    fn removed_keeps_invariants() {
+        let mut tree = RBTree::new();
+        let mut expected = std::collections::BTreeSet::new();
+        let mut seed: u32 = 17;
+        for _ in 0..2000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let x = (seed >> 16) % 64;
+            if (seed >> 8) % 3 == 1 {
+                tree = tree.removed(&x);
+                expected.remove(&x);
+            } else {
+                tree = tree.inserted(x);
+                expected.insert(x);
+            }
+            assert_eq!(tree.root_colour(), Colour::Black);
+            black_height(&tree.root);
+        }
+
+        assert_eq!(tree.iter().collect::<Vec<_>>(), expected.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn removed_missing_element_shares_tree() {
+        let t1 = RBTree::new().inserted(1).inserted(2);
+
+        let t2 = t1.removed(&3);
+
+        assert!(Rc::ptr_eq(t1.root.as_ref().unwrap(), t2.root.as_ref().unwrap()));
+        assert!(RBTree::<i32>::new().removed(&1).is_empty());
+        assert!(RBTree::leaf(1).removed(&1).is_empty());
+    }
+
+    #[test]
+    fn updated_inserts_or_replaces() {
+        let t1 = RBTree::new().inserted(KV(1, "a")).inserted(KV(2, "b"));
+
+        let t2 = t1.updated(&KV(2, ""), |kv| KV(2, kv.map_or("new", |_| "old")));
+        let t3 = t2.updated(&KV(3, ""), |kv| KV(3, kv.map_or("new", |_| "old")));
+
+        assert_eq!(t2.get(&KV(2, "")), Some(&KV(2, "old")));
+        assert_eq!(t3.get(&KV(3, "")), Some(&KV(3, "new")));
+        assert_eq!(t1.get(&KV(2, "")), Some(&KV(2, "b")));
+        black_height(&t3.root);
+    }
+
+    #[test]
+    fn iter_returns_elements_in_order() {
+        let mut t = RBTree::new();
+        for x in &[5, 3, 8, 1, 4, 7, 9, 2, 6] {
+            t = t.inserted(*x);
+        }
+
+        assert_eq!(t.iter().cloned().collect::<Vec<_>>(), (1..10).collect::<Vec<_>>());
+        assert_eq!(RBTree::<i32>::new().iter().next(), None);
+    }
+
+    #[test]
+    fn equality_ignores_shape() {
+        let t1 = RBTree::from([1, 2, 3, 4, 5]);
+        let t2 = RBTree::from(vec!(5, 4, 3, 2, 1));
+
+        assert_eq!(t1, t2);
+        assert!(t1 != t2.inserted(6));
+        assert_eq!(t1, (1..6).collect::<RBTree<_>>());
+        assert_eq!(RBTree::<i32>::default(), RBTree::new());
+    }
+
+    #[test]
+    fn debug_formats_elements_in_order() {
+        assert_eq!(format!("{:?}", RBTree::from([3, 1, 2])), "{1, 2, 3}");
+    }
+
+    #[test]
+    fn trees_compare_lexicographically() {
+        use std::collections::HashSet;
+
+        assert!(RBTree::from([1, 2]) < RBTree::from([1, 3]));
+        assert!(RBTree::from([1, 2]) < RBTree::from([2]));
+        assert!(RBTree::new() < RBTree::from([0]));
+
+        let sets: HashSet<RBSet<char>> =
+            vec!(RBTree::from(['a', 'b']), RBTree::from(['b', 'a'])).into_iter().collect();
+        assert_eq!(sets.len(), 1);
+    }
 }
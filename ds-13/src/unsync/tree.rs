@@ -1,7 +1,12 @@
-use std::rc::Rc;
+use alloc::rc::Rc;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
 use crate::unsync::list::List;
+use crate::stats::{Measure, NodeVisitor};
+use crate::traits::{Filterable, Foldable, Functor, Monad};
+use crate::Error;
 
-#[derive(Debug)]
 pub struct Tree<T> {
     root: Rc<TreeNode<T>>
 }
@@ -35,6 +40,19 @@ impl<T> Tree<T> {
         }
     }
 
+    /// Returns `true` if both trees share the same root node.
+    ///
+    /// Trees built by the same `Interner` are equal exactly when
+    /// they share the same root node.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.root, &other.root)
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn root_ptr(&self) -> usize {
+        Rc::as_ptr(&self.root) as *const () as usize
+    }
+
     pub fn root(&self) -> Option<&T> {
         match &*self.root {
             TreeNode::Empty => None,
@@ -48,6 +66,21 @@ impl<T> Tree<T> {
             TreeNode::Node(_x, children) => children,
         }
     }
+
+    /// Returns the children of the root, or `Error::EmptyTree` if
+    /// the tree is empty.
+    pub fn try_children(&self) -> Result<&List<Tree<T>>, Error> {
+        match &*self.root {
+            TreeNode::Empty => Err(Error::EmptyTree),
+            TreeNode::Node(_x, children) => Ok(children),
+        }
+    }
+}
+
+impl<T> Default for Tree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Clone for Tree<T> {
@@ -56,11 +89,107 @@ impl<T> Clone for Tree<T> {
     }
 }
 
+impl<T> Measure for Tree<T> {
+    fn visit_nodes(&self, visitor: &mut NodeVisitor) {
+        if !visitor.visit(&self.root) {
+            return;
+        }
+        if let TreeNode::Node(_x, children) = &*self.root {
+            children.visit_spine(visitor, |child, visitor| child.visit_nodes(visitor));
+        }
+    }
+}
+
+impl<T> Foldable for Tree<T> {
+    type Item = T;
+
+    fn foldl<U>(&self, mut f: impl FnMut(U, &T) -> U, acc: U) -> U {
+        foldl_tree(self, &mut f, acc)
+    }
+
+    fn foldr<U>(&self, mut f: impl FnMut(&T, U) -> U, acc: U) -> U {
+        foldr_tree(self, &mut f, acc)
+    }
+}
+
+fn foldl_tree<T, U>(tree: &Tree<T>, f: &mut impl FnMut(U, &T) -> U, acc: U) -> U {
+    match &*tree.root {
+        TreeNode::Empty => acc,
+        TreeNode::Node(x, children) => {
+            let acc = f(acc, x);
+            children.iter().fold(acc, |acc, child| foldl_tree(child, f, acc))
+        }
+    }
+}
+
+fn foldr_tree<T, U>(tree: &Tree<T>, f: &mut impl FnMut(&T, U) -> U, acc: U) -> U {
+    match &*tree.root {
+        TreeNode::Empty => acc,
+        TreeNode::Node(x, children) => {
+            let acc = children.foldr(|child, acc| foldr_tree(child, f, acc), acc);
+            f(x, acc)
+        }
+    }
+}
+
+impl<T> Functor for Tree<T> {
+    type Mapped<U> = Tree<U>;
+
+    fn fmap<U>(&self, mut f: impl FnMut(&T) -> U) -> Tree<U> {
+        fmap_tree(self, &mut f)
+    }
+}
+
+fn fmap_tree<T, U>(tree: &Tree<T>, f: &mut impl FnMut(&T) -> U) -> Tree<U> {
+    match &*tree.root {
+        TreeNode::Empty => Tree::new(),
+        TreeNode::Node(x, children) => {
+            let x = f(x);
+            Tree::tree(x, &children.fmap(|child| fmap_tree(child, f)))
+        }
+    }
+}
+
+impl<T> Monad for Tree<T> {
+    fn mreturn(x: T) -> Self {
+        Tree::leaf(x)
+    }
+
+    fn and_then<U: Clone>(&self, mut f: impl FnMut(&T) -> Tree<U>) -> Tree<U> {
+        and_then_tree(self, &mut f)
+    }
+}
+
+fn and_then_tree<T, U: Clone>(tree: &Tree<T>, f: &mut impl FnMut(&T) -> Tree<U>) -> Tree<U> {
+    match &*tree.root {
+        TreeNode::Empty => Tree::new(),
+        TreeNode::Node(x, children) => {
+            let mapped = f(x);
+            match &*mapped.root {
+                TreeNode::Empty => mapped,
+                TreeNode::Node(y, ys) => {
+                    // Subtrees mapped to empty trees are dropped
+                    let children = children.fmap(|child| and_then_tree(child, f));
+                    let children = Filterable::filter(&children, |child| !child.is_empty());
+                    let children = ys.foldr(
+                        |child, acc: List<Tree<U>>| acc.pushed_front(child.clone()),
+                        children,
+                    );
+                    Tree::tree(y.clone(), &children)
+                }
+            }
+        }
+    }
+}
+
 impl<T> PartialEq for Tree<T>
 where
     T: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
+        if self.ptr_eq(other) {
+            return true;
+        }
         match &*self.root {
             TreeNode::Empty => other.is_empty(),
             TreeNode::Node(x, xs) => {
@@ -75,6 +204,62 @@ where
     }
 }
 
+impl<T: Eq> Eq for Tree<T> {}
+
+impl<T> PartialOrd for Tree<T>
+where
+    T: PartialOrd,
+{
+    /// The empty tree is the least tree, other trees are compared
+    /// by their roots first and then by their children.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (&*self.root, &*other.root) {
+            (TreeNode::Empty, TreeNode::Empty) => Some(Ordering::Equal),
+            (TreeNode::Empty, _) => Some(Ordering::Less),
+            (_, TreeNode::Empty) => Some(Ordering::Greater),
+            (TreeNode::Node(x, xs), TreeNode::Node(y, ys)) => {
+                match x.partial_cmp(y) {
+                    Some(Ordering::Equal) => xs.partial_cmp(ys),
+                    ordering => ordering,
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord> Ord for Tree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&*self.root, &*other.root) {
+            (TreeNode::Empty, TreeNode::Empty) => Ordering::Equal,
+            (TreeNode::Empty, _) => Ordering::Less,
+            (_, TreeNode::Empty) => Ordering::Greater,
+            (TreeNode::Node(x, xs), TreeNode::Node(y, ys)) => x.cmp(y).then_with(|| xs.cmp(ys)),
+        }
+    }
+}
+
+impl<T: Hash> Hash for Tree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match &*self.root {
+            TreeNode::Empty => state.write_u8(0),
+            TreeNode::Node(x, children) => {
+                state.write_u8(1);
+                x.hash(state);
+                children.hash(state);
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Tree<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.root, f)
+    }
+}
+
 #[derive(Debug)]
 enum TreeNode<T> {
     Empty,
@@ -126,6 +311,12 @@ mod tests {
         assert_eq!(tree.children(), &children);
     }
 
+    #[test]
+    fn try_children_returns_error_on_empty_tree() {
+        assert_eq!(Tree::<i32>::new().try_children().err(), Some(Error::EmptyTree));
+        assert!(Tree::leaf(1).try_children().unwrap().is_empty());
+    }
+
     #[test]
     fn test_partial_eq() {
         let t1 = Tree::<i32>::new();
@@ -158,4 +349,21 @@ mod tests {
         assert!(t4 == t5);
         assert!(t4 != t6);
     }
+
+    #[test]
+    fn debug_and_ordering() {
+        use std::collections::HashSet;
+
+        let t1 = Tree::tree(1, &unsynced_list!(Tree::leaf(2)));
+        let t2 = Tree::tree(1, &unsynced_list!(Tree::leaf(3)));
+
+        assert_eq!(format!("{:?}", t1), "Node(1, [Node(2, [])])");
+        assert_eq!(format!("{:?}", Tree::<i32>::default()), "Empty");
+        assert!(Tree::new() < Tree::leaf(0));
+        assert!(Tree::leaf(1) < t1);
+        assert!(t1 < t2);
+
+        let trees: HashSet<Tree<i32>> = vec!(t1.clone(), t1.clone(), t2).into_iter().collect();
+        assert_eq!(trees.len(), 2);
+    }
 }
@@ -0,0 +1,87 @@
+//! Compares `extract_tree` with `List`s and with an `Arena`.
+//!
+//! Run with `cargo bench -p kxparser --features arena --bench extract_tree`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ds_13::arena::Arena;
+use kxparser::domain::models::{Chart, Rule};
+use kxparser::domain::services::arena_parsing::extract_tree_in;
+use kxparser::domain::services::chart_parsing::{earley, extract_tree};
+
+const ROUNDS: u32 = 10;
+
+const EXAMPLE_PREFIX: [&str; 5] = ["the", "lion", "sees", "a", "zebra"];
+const EXAMPLE_SUFFIX: [&str; 9] = ["under", "a", "tree", "with", "a", "telescope", "in", "the", "park"];
+
+fn rule(lhs: &str, rhs: &[&str]) -> Rule {
+    Rule::new(lhs, rhs.iter().map(|x| x.to_string()).collect())
+}
+
+fn grammar() -> Vec<Rule> {
+    vec!(
+        rule("S", &["NP", "VP"]),
+        rule("VP", &["Verb"]),
+        rule("VP", &["Verb", "NP"]),
+        rule("VP", &["VP", "PP"]),
+        rule("NP", &["Det", "Noun"]),
+        rule("NP", &["NP", "PP"]),
+        rule("PP", &["Prep", "NP"]),
+        rule("Verb", &["sees"]),
+        rule("Det", &["the"]),
+        rule("Det", &["a"]),
+        rule("Prep", &["under"]),
+        rule("Prep", &["with"]),
+        rule("Prep", &["in"]),
+        rule("Noun", &["zebra"]),
+        rule("Noun", &["lion"]),
+        rule("Noun", &["tree"]),
+        rule("Noun", &["park"]),
+        rule("Noun", &["telescope"]),
+    )
+}
+
+fn example(n: usize) -> Vec<&'static str> {
+    EXAMPLE_PREFIX.iter()
+        .chain(EXAMPLE_SUFFIX.iter().cycle().take(n * 3))
+        .copied()
+        .collect()
+}
+
+/// Returns the mean duration of `f` over `ROUNDS` runs.
+fn time<R>(mut f: impl FnMut() -> R) -> Duration {
+    black_box(f());
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(f());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn main() {
+    let grammar = grammar();
+
+    println!("{:<8} {:>8} {:>12} {:>12} {:>9}", "words", "trees", "List", "Arena", "speedup");
+    for n in 1..=5 {
+        let sentence = example(n);
+        let chart = earley(&grammar, &sentence);
+        let mut charts: Vec<Chart> = (0..=ROUNDS)
+            .map(|_| Chart { chart: chart.chart.clone() })
+            .collect();
+
+        let trees = extract_tree(Chart { chart: chart.chart.clone() }, "S").len();
+        let list = time(|| extract_tree(charts.pop().unwrap(), "S").len());
+        let arena = time(|| {
+            let arena = Arena::new();
+            extract_tree_in(&arena, &chart, "S").len()
+        });
+        println!(
+            "{:<8} {:>8} {:>12.2?} {:>12.2?} {:>8.2}x",
+            sentence.len(),
+            trees,
+            list,
+            arena,
+            list.as_secs_f64() / arena.as_secs_f64()
+        );
+    }
+}
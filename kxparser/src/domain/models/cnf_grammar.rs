@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet};
+use crate::domain::models::grammar::{Grammar, Rule};
+
+/// A part of a tree of the original grammar, where `Child(i)` stands
+/// for the trees yielded by the i-th child of a CNF rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fragment {
+    Child(usize),
+    /// A node for the rule with this index in the original grammar.
+    Node { rule: usize, children: Vec<Fragment> },
+}
+
+/// The sequence of trees of the original grammar that a CNF rule
+/// stands for.
+pub type Template = Vec<Fragment>;
+
+/// A grammar in Chomsky normal form, whose rules remember which rules
+/// of the original grammar they stand for.
+///
+/// Every rule is `A --> B C` or `A --> a`. The only empty rules are
+/// for the nullable nonterminals of the original grammar, and they
+/// are only used for the empty sentence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CnfGrammar {
+    pub grammar: Grammar,
+    /// For every rule of `grammar`, the templates it rebuilds.
+    pub provenance: Vec<Vec<Template>>,
+    pub original: Grammar,
+    index: HashMap<(String, Vec<String>), usize>,
+}
+
+impl CnfGrammar {
+    /// Returns the index of the rule `lhs --> rhs`.
+    pub fn position(&self, lhs: &str, rhs: &[&str]) -> Option<usize> {
+        let key = (lhs.to_string(), rhs.iter().map(|x| x.to_string()).collect());
+        self.index.get(&key).copied()
+    }
+}
+
+impl Grammar {
+    /// Converts the grammar to Chomsky normal form.
+    ///
+    /// Terminals in long rules get rules `<a> --> a`, and the rest of
+    /// a long rule after its first symbol becomes a nonterminal like
+    /// `<X Y>`, with `'`s appended while the name is taken by a symbol
+    /// of the grammar or another new nonterminal. Empty constituents
+    /// are dropped from the rules, using every derivation of the empty
+    /// string of each nullable symbol, except cyclic ones like
+    /// `A --> A`, `A --> `.
+    /// Unit rules are replaced by the rules they lead to, leaving out
+    /// cyclic derivations like `A --> B`, `B --> A`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kxparser::domain::models::Grammar;
+    ///
+    /// let grammar: Grammar = "
+    ///     S --> NP VP
+    ///     NP --> Det N | N
+    ///     VP --> sleeps
+    ///     Det --> the
+    ///     N --> lions
+    /// ".parse().unwrap();
+    /// let cnf = grammar.to_cnf();
+    ///
+    /// assert!(cnf.grammar.rules.iter().all(|rule| rule.rhs.len() == 2 || rule.rhs.len() == 1));
+    /// assert!(cnf.position("NP", &["lions"]).is_some());
+    /// ```
+    pub fn to_cnf(&self) -> CnfGrammar {
+        let nonterminals: HashSet<&str> = self.rules.iter().map(|rule| rule.lhs.as_str()).collect();
+        let mut names = Names {
+            symbols: self.rules.iter().flat_map(|rule| rule.rhs.iter().chain(Some(&rule.lhs))).collect(),
+            ..Names::default()
+        };
+
+        let mut rules = Rules::default();
+        for (n, rule) in self.rules.iter().enumerate() {
+            let rhs: Vec<String> = rule.rhs.iter()
+                .map(|x| {
+                    if rule.rhs.len() > 1 && !nonterminals.contains(x.as_str()) {
+                        let terminal = names.name(std::slice::from_ref(x));
+                        rules.add(&terminal, vec!(x.clone()), vec!(Fragment::Child(0)));
+                        terminal
+                    } else {
+                        x.clone()
+                    }
+                })
+                .collect();
+            let children = (0..rhs.len()).map(Fragment::Child).collect();
+            add_binarized(&mut rules, &mut names, &rule.lhs, &rhs, vec!(Fragment::Node { rule: n, children }));
+        }
+
+        let empty = empty_templates(&rules);
+        let rules = without_empty_rules(&rules, &empty);
+        let mut cnf = without_unit_rules(&rules);
+
+        let mut seen = HashSet::new();
+        for rule in &self.rules {
+            if let Some(templates) = empty.get(&rule.lhs) {
+                if seen.insert(&rule.lhs) {
+                    for template in templates {
+                        cnf.add(&rule.lhs, Vec::new(), template.clone());
+                    }
+                }
+            }
+        }
+
+        CnfGrammar {
+            grammar: Grammar::from_rules(cnf.rules),
+            provenance: cnf.templates,
+            original: self.clone(),
+            index: cnf.index,
+        }
+    }
+}
+
+/// Rules with their templates, merging the templates of equal rules.
+#[derive(Default)]
+struct Rules {
+    rules: Vec<Rule>,
+    templates: Vec<Vec<Template>>,
+    index: HashMap<(String, Vec<String>), usize>,
+}
+
+impl Rules {
+    fn add(&mut self, lhs: &str, rhs: Vec<String>, template: Template) {
+        let key = (lhs.to_string(), rhs);
+        match self.index.get(&key) {
+            Some(&n) => {
+                if !self.templates[n].contains(&template) {
+                    self.templates[n].push(template);
+                }
+            }
+            None => {
+                self.index.insert(key.clone(), self.rules.len());
+                self.rules.push(Rule::new(lhs, key.1));
+                self.templates.push(vec!(template));
+            }
+        }
+    }
+}
+
+/// The names of the new nonterminals, by the symbols they stand for.
+#[derive(Default)]
+struct Names<'g> {
+    /// The symbols of the original grammar.
+    symbols: HashSet<&'g String>,
+    names: HashMap<Vec<String>, String>,
+    taken: HashSet<String>,
+}
+
+impl Names<'_> {
+    fn name(&mut self, symbols: &[String]) -> String {
+        if let Some(name) = self.names.get(symbols) {
+            return name.clone();
+        }
+        let mut name = format!("<{}>", symbols.join(" "));
+        while self.symbols.contains(&name) || self.taken.contains(&name) {
+            name.push('\'');
+        }
+        self.taken.insert(name.clone());
+        self.names.insert(symbols.to_vec(), name.clone());
+        name
+    }
+}
+
+/// Returns `template` with every `Child(i)` replaced by `f(i)`.
+fn substituted(template: &[Fragment], f: &dyn Fn(usize) -> Template) -> Template {
+    let mut result = Vec::new();
+    for fragment in template {
+        match fragment {
+            Fragment::Child(i) => result.extend(f(*i)),
+            Fragment::Node { rule, children } => result.push(Fragment::Node {
+                rule: *rule,
+                children: substituted(children, f),
+            }),
+        }
+    }
+    result
+}
+
+fn add_binarized(rules: &mut Rules, names: &mut Names, lhs: &str, rhs: &[String], template: Template) {
+    if rhs.len() <= 2 {
+        rules.add(lhs, rhs.to_vec(), template);
+    } else {
+        let rest = names.name(&rhs[1..]);
+        let template = substituted(&template, &|i| match i {
+            0 | 1 => vec!(Fragment::Child(i)),
+            _ => Vec::new(),
+        });
+        rules.add(lhs, vec!(rhs[0].clone(), rest.clone()), template);
+        let children = (0..rhs.len() - 1).map(Fragment::Child).collect();
+        add_binarized(rules, names, &rest, &rhs[1..], children);
+    }
+}
+
+/// Returns every choice of one option for each position.
+fn choices(options: &[Vec<Template>]) -> Vec<Vec<&Template>> {
+    options.iter().fold(vec!(Vec::new()), |choices, templates| {
+        choices.iter()
+            .flat_map(|choice| templates.iter().map(move |template| {
+                let mut choice = choice.clone();
+                choice.push(template);
+                choice
+            }))
+            .collect()
+    })
+}
+
+/// Returns the templates of the derivations of the empty string, for
+/// every nullable symbol. Derivations where a symbol derives itself,
+/// which would make them infinitely many, are left out.
+fn empty_templates(rules: &Rules) -> HashMap<String, Vec<Template>> {
+    let mut empty = HashMap::new();
+    for rule in &rules.rules {
+        if !empty.contains_key(&rule.lhs) {
+            let templates = derivations_of_empty(rules, &rule.lhs, &mut vec!(rule.lhs.as_str()));
+            if !templates.is_empty() {
+                empty.insert(rule.lhs.clone(), templates);
+            }
+        }
+    }
+    empty
+}
+
+fn derivations_of_empty<'a>(rules: &'a Rules, symbol: &str, path: &mut Vec<&'a str>) -> Vec<Template> {
+    let mut result = Vec::new();
+    for (rule, templates) in rules.rules.iter().zip(&rules.templates) {
+        if rule.lhs != symbol || rule.rhs.iter().any(|x| path.contains(&x.as_str())) {
+            continue;
+        }
+        let options: Vec<Vec<Template>> = rule.rhs.iter()
+            .map(|x| {
+                path.push(x);
+                let options = derivations_of_empty(rules, x, path);
+                path.pop();
+                options
+            })
+            .collect();
+        for choice in choices(&options) {
+            for template in templates {
+                let template = substituted(template, &|i| choice[i].clone());
+                if !result.contains(&template) {
+                    result.push(template);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Returns the rules with every combination of nullable symbols
+/// dropped, except all of them.
+fn without_empty_rules(rules: &Rules, empty: &HashMap<String, Vec<Template>>) -> Rules {
+    let mut result = Rules::default();
+    for (rule, templates) in rules.rules.iter().zip(&rules.templates) {
+        let n = rule.rhs.len();
+        for dropped in 0..(1 << n) {
+            let is_dropped = |i: usize| dropped & (1 << i) != 0;
+            if (0..n).any(|i| is_dropped(i) && !empty.contains_key(&rule.rhs[i])) {
+                continue;
+            }
+            let kept: Vec<usize> = (0..n).filter(|i| !is_dropped(*i)).collect();
+            if kept.is_empty() {
+                continue;
+            }
+            let rhs = kept.iter().map(|i| rule.rhs[*i].clone()).collect::<Vec<_>>();
+            let options: Vec<Vec<Template>> = (0..n)
+                .map(|i| match kept.iter().position(|k| *k == i) {
+                    Some(k) => vec!(vec!(Fragment::Child(k))),
+                    None => empty[&rule.rhs[i]].clone(),
+                })
+                .collect();
+            for choice in choices(&options) {
+                for template in templates {
+                    let template = substituted(template, &|i| choice[i].clone());
+                    result.add(&rule.lhs, rhs.clone(), template);
+                }
+            }
+        }
+    }
+
+    // Nonterminals that only derive the empty string have no rules left
+    let nonterminals: HashSet<&str> = rules.rules.iter().map(|rule| rule.lhs.as_str()).collect();
+    loop {
+        let defined: HashSet<&str> = result.rules.iter().map(|rule| rule.lhs.as_str()).collect();
+        let is_defined = |x: &String| !nonterminals.contains(x.as_str()) || defined.contains(x.as_str());
+        if result.rules.iter().all(|rule| rule.rhs.iter().all(is_defined)) {
+            return result;
+        }
+        let mut retained = Rules::default();
+        for (rule, templates) in result.rules.iter().zip(&result.templates) {
+            if rule.rhs.iter().all(is_defined) {
+                for template in templates {
+                    retained.add(&rule.lhs, rule.rhs.clone(), template.clone());
+                }
+            }
+        }
+        result = retained;
+    }
+}
+
+/// Returns the rules with every chain of unit rules `A --> B` replaced
+/// by rules from `A` to the rhs of the non-unit rules at its end.
+fn without_unit_rules(rules: &Rules) -> Rules {
+    let mut by_lhs: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (n, rule) in rules.rules.iter().enumerate() {
+        by_lhs.entry(rule.lhs.as_str()).or_default().push(n);
+    }
+    let mut result = Rules::default();
+    let mut seen = HashSet::new();
+    for rule in &rules.rules {
+        if seen.insert(rule.lhs.as_str()) {
+            let mut visited = vec!(rule.lhs.as_str());
+            let identity = [Fragment::Child(0)];
+            add_unit_closure(rules, &by_lhs, &rule.lhs, &rule.lhs, &identity, &mut visited, &mut result);
+        }
+    }
+    result
+}
+
+fn add_unit_closure<'a>(
+    rules: &'a Rules,
+    by_lhs: &HashMap<&str, Vec<usize>>,
+    lhs: &str,
+    symbol: &str,
+    outer: &[Fragment],
+    visited: &mut Vec<&'a str>,
+    result: &mut Rules,
+) {
+    for &n in by_lhs.get(symbol).into_iter().flatten() {
+        let rule = &rules.rules[n];
+        for template in &rules.templates[n] {
+            let template = substituted(outer, &|_| template.clone());
+            match rule.rhs.as_slice() {
+                [x] if by_lhs.contains_key(x.as_str()) => {
+                    if !visited.contains(&x.as_str()) {
+                        visited.push(x);
+                        add_unit_closure(rules, by_lhs, lhs, x, &template, visited, result);
+                        visited.pop();
+                    }
+                }
+                rhs => result.add(lhs, rhs.to_vec(), template),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(cnf: &CnfGrammar) -> Vec<String> {
+        cnf.grammar.rules.iter().map(|rule| rule.to_string()).collect()
+    }
+
+    fn node(rule: usize, children: Vec<Fragment>) -> Fragment {
+        Fragment::Node { rule, children }
+    }
+
+    #[test]
+    fn binarizes_long_rules() {
+        let grammar: Grammar = "S --> A b C\nA --> a\nC --> c".parse().unwrap();
+
+        let cnf = grammar.to_cnf();
+
+        assert_eq!(
+            rules(&cnf),
+            vec!("<b> --> b", "S --> A <<b> C>", "<<b> C> --> <b> C", "A --> a", "C --> c")
+        );
+        assert_eq!(
+            cnf.provenance[1],
+            vec!(vec!(node(0, vec!(Fragment::Child(0), Fragment::Child(1)))))
+        );
+        assert_eq!(cnf.provenance[2], vec!(vec!(Fragment::Child(0), Fragment::Child(1))));
+    }
+
+    #[test]
+    fn new_names_avoid_symbols_of_the_grammar() {
+        let symbols = |xs: &[&str]| xs.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let grammar = Grammar::from_rules(vec!(
+            Rule::new("S", symbols(&["A", "B", "C"])),
+            Rule::new("S", symbols(&["<B C>"])),
+            Rule::new("<B C>", symbols(&["a", "<a>"])),
+            Rule::new("A", symbols(&["a"])),
+            Rule::new("B", symbols(&["b"])),
+            Rule::new("C", symbols(&["c"])),
+        ));
+
+        let cnf = grammar.to_cnf();
+
+        assert!(cnf.position("S", &["A", "<B C>'"]).is_some());
+        assert!(cnf.position("<B C>'", &["B", "C"]).is_some());
+        assert!(cnf.position("<a>'", &["a"]).is_some());
+        assert!(cnf.position("<<a>>", &["<a>"]).is_some());
+        assert!(cnf.position("S", &["<a>'", "<<a>>"]).is_some());
+        assert!(cnf.position("<B C>", &["<a>'", "<<a>>"]).is_some());
+        assert!(cnf.position("<B C>", &["B", "C"]).is_none());
+        assert!(cnf.position("<a>", &["a"]).is_none());
+    }
+
+    #[test]
+    fn eliminates_empty_and_unit_rules() {
+        let grammar: Grammar = "
+            S --> A A
+            A --> a | B
+            B -->
+        ".parse().unwrap();
+
+        let cnf = grammar.to_cnf();
+
+        assert_eq!(rules(&cnf), vec!("S --> A A", "S --> a", "A --> a", "S --> ", "A --> ", "B --> "));
+        let empty_a = node(2, vec!(node(3, Vec::new())));
+        assert_eq!(
+            cnf.provenance[1],
+            vec!(
+                vec!(node(0, vec!(empty_a.clone(), node(1, vec!(Fragment::Child(0)))))),
+                vec!(node(0, vec!(node(1, vec!(Fragment::Child(0))), empty_a))),
+            )
+        );
+        assert_eq!(cnf.position("S", &[]), Some(3));
+    }
+
+    #[test]
+    fn leaves_out_unit_cycles() {
+        let grammar: Grammar = "A --> B | a\nB --> A | b".parse().unwrap();
+
+        let cnf = grammar.to_cnf();
+
+        assert_eq!(rules(&cnf), vec!("A --> b", "A --> a", "B --> a", "B --> b"));
+    }
+}
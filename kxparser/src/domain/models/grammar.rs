@@ -1,15 +1,20 @@
+use std::collections::HashMap;
 use std::fmt;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Grammar {
     pub rules: Vec<Rule>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Rule {
     pub lhs: String,
     pub rhs: Vec<String>,
+    /// The probability of the rule given its lhs, if the grammar is
+    /// probabilistic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
 }
 
 impl Grammar {
@@ -27,8 +32,47 @@ impl Rule {
         Rule {
             lhs: lhs.to_string(),
             rhs: rhs,
+            weight: None,
         }
     }
+
+    pub fn weighted(lhs: &str, rhs: Vec<String>, weight: f64) -> Self {
+        Rule {
+            lhs: lhs.to_string(),
+            rhs,
+            weight: Some(weight),
+        }
+    }
+
+    /// Returns the weight, where rules without a weight count as
+    /// certain. The probabilities that parsing and training use come
+    /// from `rule_probabilities`, which looks at the other rules.
+    pub fn probability(&self) -> f64 {
+        self.weight.unwrap_or(1.0)
+    }
+}
+
+/// Returns the probability of every rule given its lhs, where the
+/// rules without a weight share what is left for their lhs. Duplicate
+/// rules keep their own probabilities, which add up.
+pub fn rule_probabilities(rules: &[Rule]) -> Vec<f64> {
+    let mut weighted: HashMap<&str, f64> = HashMap::new();
+    let mut unweighted: HashMap<&str, usize> = HashMap::new();
+    for rule in rules {
+        match rule.weight {
+            Some(weight) => *weighted.entry(&rule.lhs).or_insert(0.0) += weight,
+            None => *unweighted.entry(&rule.lhs).or_insert(0) += 1,
+        }
+    }
+    rules.iter()
+        .map(|rule| match rule.weight {
+            Some(weight) => weight,
+            None => {
+                let rest = 1.0 - weighted.get(rule.lhs.as_str()).copied().unwrap_or(0.0);
+                rest.max(0.0) / unweighted[rule.lhs.as_str()] as f64
+            }
+        })
+        .collect()
 }
 
 impl fmt::Display for Grammar {
@@ -48,7 +92,11 @@ impl fmt::Display for Grammar {
 
 impl fmt::Display for Rule {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} --> {}", self.lhs, self.rhs.join(" "))
+        write!(f, "{} --> {}", self.lhs, self.rhs.join(" "))?;
+        match self.weight {
+            Some(weight) => write!(f, " [{}]", weight),
+            None => Ok(()),
+        }
     }
 }
 
@@ -66,6 +114,36 @@ mod tests {
         assert_eq!(format!("{}", rule), "test --> ");
     }
 
+    #[test]
+    fn weighted_creates_rule_with_weight() {
+        let rule = Rule::weighted("NP", vec!("Det".to_string(), "N".to_string()), 0.25);
+
+        assert_eq!(rule.weight, Some(0.25));
+        assert_eq!(rule.probability(), 0.25);
+        assert_eq!(Rule::new("NP", Vec::new()).probability(), 1.0);
+        assert_eq!(format!("{}", rule), "NP --> Det N [0.25]");
+    }
+
+    #[test]
+    fn unweighted_rules_share_the_rest() {
+        let grammar: Grammar = "S --> a [0.4] | b | c\nT --> d | d\nU --> e [0.7] | f [0.5] | g".parse().unwrap();
+
+        assert_eq!(rule_probabilities(&grammar.rules), vec!(0.4, 0.3, 0.3, 0.5, 0.5, 0.7, 0.5, 0.0));
+    }
+
+    #[test]
+    fn weight_is_optional_in_ron() {
+        let rules: Vec<Rule> = ron::de::from_str(
+            r#"[(lhs:"S",rhs:["NP","VP"]),(lhs:"NP",rhs:["John"],weight:Some(0.5))]"#
+        ).unwrap();
+
+        assert_eq!(rules[0].weight, None);
+        assert_eq!(rules[1].weight, Some(0.5));
+        assert_eq!(ron::ser::to_string(&rules[0]).unwrap(), r#"(lhs:"S",rhs:["NP","VP"])"#);
+        let rule: Rule = ron::de::from_str(&ron::ser::to_string(&rules[1]).unwrap()).unwrap();
+        assert_eq!(rule, rules[1]);
+    }
+
     #[test]
     fn new_creates_empty_grammar() {
         let grammar = Grammar::new();
@@ -0,0 +1,328 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+use ds_13::unsync::graph::Graph;
+use crate::domain::models::grammar::Grammar;
+
+/// The result of `Grammar::analyze`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrammarAnalysis<'g> {
+    /// The lhs of the first rule.
+    pub start: Option<&'g str>,
+    pub nonterminals: BTreeSet<&'g str>,
+    pub terminals: BTreeSet<&'g str>,
+    /// The nonterminals that can derive the empty string.
+    pub nullable: BTreeSet<&'g str>,
+    pub diagnostics: Vec<Diagnostic<'g>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+/// A problem found by `Grammar::analyze`, referring to rules by
+/// their index in `Grammar::rules`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic<'g> {
+    /// A symbol without rules that starts with an uppercase letter and
+    /// isn't the whole rhs of a rule.
+    UndefinedNonterminal { symbol: &'g str, rule: usize },
+    /// A symbol that no derivation from the start symbol uses.
+    Unreachable { symbol: &'g str },
+    /// A nonterminal that derives no string of terminals.
+    Unproductive { symbol: &'g str },
+    /// Nonterminals that derive themselves as left corner.
+    LeftRecursion { cycle: Vec<&'g str> },
+    /// A rule with the same lhs and rhs as an earlier rule.
+    DuplicateRule { rule: usize, first: usize },
+}
+
+impl Diagnostic<'_> {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnostic::UndefinedNonterminal { .. } => Severity::Warning,
+            Diagnostic::Unproductive { .. } => Severity::Error,
+            Diagnostic::Unreachable { .. } => Severity::Warning,
+            Diagnostic::DuplicateRule { .. } => Severity::Warning,
+            Diagnostic::LeftRecursion { .. } => Severity::Note,
+        }
+    }
+}
+
+impl GrammarAnalysis<'_> {
+    /// Returns `true` if no diagnostic is an error.
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.iter().all(|d| d.severity() < Severity::Error)
+    }
+}
+
+impl Grammar {
+    /// Checks the grammar and classifies its symbols.
+    ///
+    /// The text format doesn't mark terminals, so a symbol without
+    /// rules counts as a terminal. If it starts with an uppercase
+    /// letter, like the nonterminals `S` and `NP`, it may be a
+    /// nonterminal whose rules are missing, and is reported as an
+    /// undefined nonterminal, unless it's the whole rhs of a rule, like
+    /// the word `John` in `NP --> John`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kxparser::domain::models::Grammar;
+    /// use kxparser::domain::models::grammar_analysis::Diagnostic;
+    ///
+    /// let grammar: Grammar = "
+    ///     S --> NP VP
+    ///     NP --> NP PP | John
+    ///     VP --> runs
+    /// ".parse().unwrap();
+    /// let analysis = grammar.analyze();
+    ///
+    /// assert!(analysis.is_ok());
+    /// assert!(analysis.diagnostics.contains(
+    ///     &Diagnostic::UndefinedNonterminal { symbol: "PP", rule: 1 }
+    /// ));
+    /// assert!(!analysis.diagnostics.iter().any(|diagnostic| matches!(
+    ///     diagnostic,
+    ///     Diagnostic::UndefinedNonterminal { symbol: "John", .. }
+    ///         | Diagnostic::Unproductive { .. }
+    /// )));
+    /// assert!(analysis.diagnostics.contains(
+    ///     &Diagnostic::LeftRecursion { cycle: vec!("NP") }
+    /// ));
+    /// ```
+    pub fn analyze(&self) -> GrammarAnalysis<'_> {
+        let rules: Vec<(&str, Vec<&str>)> = self.rules.iter()
+            .map(|rule| (rule.lhs.as_str(), rule.rhs.iter().map(String::as_str).collect()))
+            .collect();
+        let start = rules.first().map(|(lhs, _)| *lhs);
+        let nonterminals: BTreeSet<&str> = rules.iter().map(|(lhs, _)| *lhs).collect();
+        let mut terminals = BTreeSet::new();
+        let mut diagnostics = Vec::new();
+
+        for (n, (_, rhs)) in rules.iter().enumerate() {
+            for symbol in rhs.iter().filter(|x| !nonterminals.contains(*x)) {
+                if rhs.len() > 1 && symbol.starts_with(char::is_uppercase) {
+                    diagnostics.push(Diagnostic::UndefinedNonterminal { symbol, rule: n });
+                }
+                terminals.insert(*symbol);
+            }
+        }
+
+        if let Some(start) = start {
+            let graph = rules.iter().fold(Graph::new(), |graph, (lhs, rhs)| {
+                rhs.iter().fold(graph.with_node(*lhs), |graph, x| graph.with_edge(*lhs, *x))
+            });
+            let reachable: BTreeSet<&str> = graph.bfs(&start).copied().collect();
+            let symbols = nonterminals.iter().chain(&terminals);
+            for symbol in symbols.filter(|x| !reachable.contains(*x)) {
+                diagnostics.push(Diagnostic::Unreachable { symbol });
+            }
+        }
+
+        let productive = fixpoint(&rules, |x, productive| {
+            terminals.contains(x) || productive.contains(x)
+        });
+        for symbol in nonterminals.iter().filter(|x| !productive.contains(*x)) {
+            diagnostics.push(Diagnostic::Unproductive { symbol });
+        }
+
+        let nullable = fixpoint(&rules, |x, nullable| nullable.contains(x));
+        diagnostics.extend(left_recursion(&rules, &nullable));
+
+        let mut first_rules = HashMap::new();
+        for (n, rule) in rules.iter().enumerate() {
+            if let Some(first) = first_rules.insert(rule, n) {
+                first_rules.insert(rule, first);
+                diagnostics.push(Diagnostic::DuplicateRule { rule: n, first });
+            }
+        }
+
+        GrammarAnalysis { start, nonterminals, terminals, nullable, diagnostics }
+    }
+}
+
+/// Returns the least set of lhs with a rule whose rhs symbols all
+/// satisfy `p`, given the set so far.
+fn fixpoint<'g>(
+    rules: &[(&'g str, Vec<&'g str>)],
+    p: impl Fn(&str, &BTreeSet<&'g str>) -> bool,
+) -> BTreeSet<&'g str> {
+    let mut result = BTreeSet::new();
+    loop {
+        let size = result.len();
+        for (lhs, rhs) in rules {
+            if !result.contains(lhs) && rhs.iter().all(|x| p(x, &result)) {
+                result.insert(*lhs);
+            }
+        }
+        if result.len() == size {
+            return result;
+        }
+    }
+}
+
+/// Returns the cycles of the left-corner relation, where the left
+/// corners of a rule are the rhs symbols after a nullable prefix.
+fn left_recursion<'g>(
+    rules: &[(&'g str, Vec<&'g str>)],
+    nullable: &BTreeSet<&'g str>,
+) -> Vec<Diagnostic<'g>> {
+    let mut graph = Graph::new();
+    for (lhs, rhs) in rules {
+        graph = graph.with_node(*lhs);
+        for x in rhs {
+            graph = graph.with_edge(*lhs, *x);
+            if !nullable.contains(x) {
+                break;
+            }
+        }
+    }
+    graph.strongly_connected_components()
+        .into_iter()
+        .filter(|cycle| cycle.len() > 1 || graph.contains_edge(cycle[0], cycle[0]))
+        .map(|cycle| Diagnostic::LeftRecursion { cycle: cycle.into_iter().copied().collect() })
+        .collect()
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.severity())?;
+        match self {
+            Diagnostic::UndefinedNonterminal { symbol, rule } => {
+                write!(f, "`{}` in rule {} has no rules", symbol, rule)
+            }
+            Diagnostic::Unreachable { symbol } => {
+                write!(f, "`{}` is unreachable from the start symbol", symbol)
+            }
+            Diagnostic::Unproductive { symbol } => {
+                write!(f, "`{}` derives no string of terminals", symbol)
+            }
+            Diagnostic::LeftRecursion { cycle } => {
+                write!(f, "left recursion through `{}`", cycle.join("`, `"))
+            }
+            Diagnostic::DuplicateRule { rule, first } => {
+                write!(f, "rule {} duplicates rule {}", rule, first)
+            }
+        }
+    }
+}
+
+impl fmt::Display for GrammarAnalysis<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |symbols: &BTreeSet<&str>| symbols.iter().copied().collect::<Vec<_>>().join(" ");
+        writeln!(f, "start symbol: {}", self.start.unwrap_or("-"))?;
+        writeln!(f, "nonterminals: {}", join(&self.nonterminals))?;
+        writeln!(f, "terminals: {}", join(&self.terminals))?;
+        write!(f, "nullable: {}", join(&self.nullable))?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_symbols() {
+        let grammar: Grammar = "
+            S --> NP VP
+            NP --> Det Noun
+            VP --> Verb NP | sleeps
+            Det --> the | a
+            Noun --> lion
+        ".parse().unwrap();
+
+        let analysis = grammar.analyze();
+
+        assert!(analysis.is_ok());
+        assert_eq!(analysis.start, Some("S"));
+        assert_eq!(analysis.nonterminals.len(), 5);
+        assert_eq!(
+            analysis.terminals,
+            vec!("Verb", "a", "lion", "sleeps", "the").into_iter().collect()
+        );
+        assert_eq!(
+            analysis.diagnostics,
+            vec!(Diagnostic::UndefinedNonterminal { symbol: "Verb", rule: 2 })
+        );
+    }
+
+    #[test]
+    fn finds_unreachable_and_unproductive_symbols() {
+        let grammar: Grammar = "
+            S --> A | b
+            A --> A c
+            D --> e
+        ".parse().unwrap();
+
+        assert_eq!(
+            grammar.analyze().diagnostics,
+            vec!(
+                Diagnostic::Unreachable { symbol: "D" },
+                Diagnostic::Unreachable { symbol: "e" },
+                Diagnostic::Unproductive { symbol: "A" },
+                Diagnostic::LeftRecursion { cycle: vec!("A") },
+            )
+        );
+    }
+
+    #[test]
+    fn finds_nullable_symbols_and_hidden_left_recursion() {
+        let grammar: Grammar = "
+            S --> E T
+            E --> | e
+            T --> S t | t
+        ".parse().unwrap();
+
+        let analysis = grammar.analyze();
+
+        assert_eq!(analysis.nullable, vec!("E").into_iter().collect());
+        assert_eq!(analysis.diagnostics, vec!(Diagnostic::LeftRecursion { cycle: vec!("S", "T") }));
+    }
+
+    #[test]
+    fn finds_duplicate_rules() {
+        let grammar: Grammar = "S --> a | b | a\nS --> a".parse().unwrap();
+
+        assert_eq!(
+            grammar.analyze().diagnostics,
+            vec!(
+                Diagnostic::DuplicateRule { rule: 2, first: 0 },
+                Diagnostic::DuplicateRule { rule: 3, first: 0 },
+            )
+        );
+    }
+
+    #[test]
+    fn displays_report() {
+        let grammar: Grammar = "S --> S x | ".parse().unwrap();
+
+        assert_eq!(
+            grammar.analyze().to_string(),
+            "start symbol: S\n\
+             nonterminals: S\n\
+             terminals: x\n\
+             nullable: S\n\
+             note: left recursion through `S`"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use std::{
+    error,
+    fmt,
+    str::FromStr,
+};
+use crate::domain::models::grammar::{Grammar, Rule};
+
+const HEADER: &str = "grammar rules:";
+const ARROW: &str = "-->";
+
+/// A syntax error in a grammar, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGrammarError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseGrammarErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGrammarErrorKind {
+    /// A rule doesn't start with its lhs.
+    MissingLhs,
+    /// The lhs isn't followed by `-->`.
+    MissingArrow,
+    /// A `-->` in the rhs of a rule.
+    UnexpectedArrow,
+    /// A line starts with `|`, but doesn't continue a rule.
+    DanglingAlternative,
+    /// The `grammar rules:` header after the first rule.
+    MisplacedHeader,
+    /// A number in brackets that isn't a weight from 0 to 1, like
+    /// `[0.5x]` or `[2]`.
+    InvalidWeight,
+    /// A symbol after the weight of an alternative.
+    SymbolAfterWeight,
+}
+
+impl fmt::Display for ParseGrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseGrammarErrorKind::MissingLhs => write!(f, "expected a symbol"),
+            ParseGrammarErrorKind::MissingArrow => write!(f, "expected `{}`", ARROW),
+            ParseGrammarErrorKind::UnexpectedArrow => write!(f, "unexpected `{}`", ARROW),
+            ParseGrammarErrorKind::DanglingAlternative => write!(f, "`|` doesn't continue a rule"),
+            ParseGrammarErrorKind::MisplacedHeader => write!(f, "`{}` must come first", HEADER),
+            ParseGrammarErrorKind::InvalidWeight => write!(f, "expected a weight from 0 to 1, like `[0.5]`"),
+            ParseGrammarErrorKind::SymbolAfterWeight => write!(f, "expected `|` after the weight"),
+        }
+    }
+}
+
+impl error::Error for ParseGrammarError {}
+
+/// Parses the text format that `Grammar` is displayed in.
+///
+/// Every line holds a rule `lhs --> rhs`, where `rhs` is zero or more
+/// symbols separated by whitespace. Alternatives for the same lhs
+/// are separated by `|`, and a line starting with `|` adds more
+/// alternatives to the rule above it. Everything after `#` is a
+/// comment. The text may start with a `grammar rules:` header.
+/// An alternative may end with its weight, a number from 0 to 1 in
+/// brackets like `[0.5]`. Other tokens in brackets that start like a
+/// number, e.g. `[0.5x]` or `[-1]`, are errors, so no symbol can look
+/// like a weight, but other symbols may contain brackets, like `[x]`.
+///
+/// # Examples
+///
+/// ```
+/// use kxparser::domain::models::Grammar;
+///
+/// let grammar: Grammar = "
+///     S --> NP VP          # a sentence
+///     VP --> Verb | Verb NP
+///        | VP PP
+/// ".parse().unwrap();
+///
+/// assert_eq!(grammar.rules.len(), 4);
+/// assert_eq!(grammar.rules[3].to_string(), "VP --> VP PP");
+/// assert_eq!(grammar.to_string().parse::<Grammar>().unwrap(), grammar);
+/// ```
+impl FromStr for Grammar {
+    type Err = ParseGrammarError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules: Vec<Rule> = Vec::new();
+        let mut lhs: Option<String> = None;
+        for (n, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let error = |column, kind| ParseGrammarError { line: n + 1, column, kind };
+            if line.trim() == HEADER {
+                if !rules.is_empty() {
+                    let col = column(line, line.trim());
+                    return Err(error(col, ParseGrammarErrorKind::MisplacedHeader));
+                }
+                continue;
+            }
+            let mut tokens = tokenize(line);
+            let (rule_lhs, mut rhs) = match tokens.next() {
+                None => continue,
+                Some((col, "|")) => match &lhs {
+                    None => return Err(error(col, ParseGrammarErrorKind::DanglingAlternative)),
+                    Some(lhs) => (lhs.clone(), Vec::new()),
+                },
+                Some((col, ARROW)) => return Err(error(col, ParseGrammarErrorKind::MissingLhs)),
+                Some((_, symbol)) => match tokens.next() {
+                    Some((_, ARROW)) => (symbol.to_string(), Vec::new()),
+                    Some((col, _)) => return Err(error(col, ParseGrammarErrorKind::MissingArrow)),
+                    None => {
+                        let col = line.trim_end().chars().count() + 1;
+                        return Err(error(col, ParseGrammarErrorKind::MissingArrow));
+                    }
+                },
+            };
+            let mut weight = None;
+            for (col, token) in tokens {
+                match token {
+                    ARROW => return Err(error(col, ParseGrammarErrorKind::UnexpectedArrow)),
+                    "|" => {
+                        rules.push(Rule { lhs: rule_lhs.clone(), rhs, weight });
+                        rhs = Vec::new();
+                        weight = None;
+                    }
+                    _ if weight.is_some() => {
+                        return Err(error(col, ParseGrammarErrorKind::SymbolAfterWeight));
+                    }
+                    _ if looks_like_weight(token) => match parse_weight(token) {
+                        Some(w) => weight = Some(w),
+                        None => return Err(error(col, ParseGrammarErrorKind::InvalidWeight)),
+                    },
+                    symbol => rhs.push(symbol.to_string()),
+                }
+            }
+            rules.push(Rule { lhs: rule_lhs.clone(), rhs, weight });
+            lhs = Some(rule_lhs);
+        }
+        Ok(Grammar::from_rules(rules))
+    }
+}
+
+/// Returns `true` if the token is meant as a weight: brackets around
+/// something that starts like a number or parses as one, like `inf`.
+fn looks_like_weight(token: &str) -> bool {
+    match token.strip_prefix('[').and_then(|token| token.strip_suffix(']')) {
+        Some(number) => {
+            number.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c))
+                || number.parse::<f64>().is_ok()
+        }
+        None => false,
+    }
+}
+
+fn parse_weight(token: &str) -> Option<f64> {
+    let weight: f64 = token.strip_prefix('[')?.strip_suffix(']')?.parse().ok()?;
+    if (0.0..=1.0).contains(&weight) {
+        Some(weight)
+    } else {
+        None
+    }
+}
+
+/// Returns the 1-based column where `part`, a subslice of `line`, starts.
+fn column(line: &str, part: &str) -> usize {
+    line[..part.as_ptr() as usize - line.as_ptr() as usize].chars().count() + 1
+}
+
+/// Splits a line into symbols and `|`s, with their 1-based columns.
+fn tokenize(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .flat_map(move |word| {
+            let mut tokens = Vec::new();
+            let mut rest = word;
+            while let Some(i) = rest.find('|') {
+                if i > 0 {
+                    tokens.push(&rest[..i]);
+                }
+                tokens.push(&rest[i..i + 1]);
+                rest = &rest[i + 1..];
+            }
+            if !rest.is_empty() {
+                tokens.push(rest);
+            }
+            tokens
+        })
+        .map(move |token| (column(line, token), token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(grammar: &Grammar) -> Vec<String> {
+        grammar.rules.iter().map(|rule| rule.to_string()).collect()
+    }
+
+    fn error(s: &str) -> (usize, usize, ParseGrammarErrorKind) {
+        let e = s.parse::<Grammar>().unwrap_err();
+        (e.line, e.column, e.kind)
+    }
+
+    #[test]
+    fn parses_alternatives_and_comments() {
+        let grammar: Grammar = "
+# lexicon
+Det --> the|a
+NP --> Det Noun | NP PP   # recursive
+   | Name
+Empty -->
+".parse().unwrap();
+
+        assert_eq!(
+            rules(&grammar),
+            vec!(
+                "Det --> the",
+                "Det --> a",
+                "NP --> Det Noun",
+                "NP --> NP PP",
+                "NP --> Name",
+                "Empty --> ",
+            )
+        );
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let grammar = Grammar::from_rules(vec!(
+            Rule::new("S", vec!("NP".to_string(), "VP".to_string())),
+            Rule::new("VP", vec!("sees".to_string())),
+            Rule::new("X", Vec::new()),
+        ));
+
+        let parsed: Grammar = grammar.to_string().parse().unwrap();
+
+        assert_eq!(parsed, grammar);
+        assert_eq!("".parse::<Grammar>().unwrap(), Grammar::new());
+        assert_eq!(Grammar::new().to_string().parse::<Grammar>().unwrap(), Grammar::new());
+    }
+
+    #[test]
+    fn parses_weights() {
+        let grammar: Grammar = "NP --> Det N [0.75] | John [0.25]\nN --> dog".parse().unwrap();
+
+        assert_eq!(
+            grammar.rules.iter().map(|rule| rule.weight).collect::<Vec<_>>(),
+            vec!(Some(0.75), Some(0.25), None)
+        );
+        assert_eq!(grammar.to_string().parse::<Grammar>().unwrap(), grammar);
+    }
+
+    #[test]
+    fn brackets_are_symbols_unless_a_number() {
+        let grammar = Grammar::from_rules(vec!(
+            Rule::new("S", vec!("[".to_string(), "[x]".to_string())),
+            Rule::weighted("S", vec!("[x]".to_string(), "]".to_string()), 0.5),
+            Rule::weighted("S", vec!("[]".to_string()), 1.0),
+        ));
+
+        assert_eq!(rules(&grammar), vec!("S --> [ [x]", "S --> [x] ] [0.5]", "S --> [] [1]"));
+        assert_eq!(grammar.to_string().parse::<Grammar>().unwrap(), grammar);
+    }
+
+    #[test]
+    fn rejects_invalid_weights() {
+        assert_eq!(error("S --> a [0.5x]"), (1, 9, ParseGrammarErrorKind::InvalidWeight));
+        assert_eq!(error("S --> a [nan]"), (1, 9, ParseGrammarErrorKind::InvalidWeight));
+        assert_eq!(error("S --> a [inf]"), (1, 9, ParseGrammarErrorKind::InvalidWeight));
+        assert_eq!(error("S --> a [-1]"), (1, 9, ParseGrammarErrorKind::InvalidWeight));
+        assert_eq!(error("S --> a [2] | b"), (1, 9, ParseGrammarErrorKind::InvalidWeight));
+        assert_eq!(error("S --> a [0.5] b"), (1, 15, ParseGrammarErrorKind::SymbolAfterWeight));
+        assert_eq!(error("S --> a [0.5] [0.5]"), (1, 15, ParseGrammarErrorKind::SymbolAfterWeight));
+        assert_eq!(
+            "S --> a [2]".parse::<Grammar>().unwrap_err().to_string(),
+            "line 1, column 9: expected a weight from 0 to 1, like `[0.5]`"
+        );
+    }
+
+    #[test]
+    fn errors_have_line_and_column() {
+        assert_eq!(error("S --> A\nNP VP"), (2, 4, ParseGrammarErrorKind::MissingArrow));
+        assert_eq!(error("S"), (1, 2, ParseGrammarErrorKind::MissingArrow));
+        assert_eq!(error("  --> A"), (1, 3, ParseGrammarErrorKind::MissingLhs));
+        assert_eq!(error("S --> A --> B"), (1, 9, ParseGrammarErrorKind::UnexpectedArrow));
+        assert_eq!(error("\n  | A"), (2, 3, ParseGrammarErrorKind::DanglingAlternative));
+        assert_eq!(error("S --> A\ngrammar rules:"), (2, 1, ParseGrammarErrorKind::MisplacedHeader));
+        assert_eq!(
+            "S --> A\nNP VP".parse::<Grammar>().unwrap_err().to_string(),
+            "line 2, column 4: expected `-->`"
+        );
+    }
+}
@@ -1,7 +1,13 @@
 pub mod grammar;
 pub mod edge;
 pub mod chart;
+pub mod grammar_parser;
+pub mod grammar_analysis;
+pub mod cnf_grammar;
 
 pub use grammar::{Grammar, Rule};
 pub use edge::Edge;
 pub use chart::Chart;
+pub use grammar_parser::ParseGrammarError;
+pub use grammar_analysis::{Diagnostic, GrammarAnalysis};
+pub use cnf_grammar::CnfGrammar;
@@ -0,0 +1,154 @@
+use std::fmt;
+
+use ds_13::arena::{Arena, ArenaList};
+use ds_13::unsync::list::List;
+use crate::domain::models::Chart;
+use crate::domain::services::chart_parsing::{is_empty_at, topdown_map, TopdownMap, Tree};
+
+/// A parse tree whose children live in an `Arena`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArenaTree<'t> {
+    root: &'t str,
+    children: ArenaList<'t, ArenaTree<'t>>,
+}
+
+impl<'t> ArenaTree<'t> {
+    pub fn new(root: &'t str, children: ArenaList<'t, ArenaTree<'t>>) -> Self {
+        ArenaTree { root, children }
+    }
+
+    pub fn root(&self) -> &'t str {
+        self.root
+    }
+
+    pub fn children(&self) -> ArenaList<'t, ArenaTree<'t>> {
+        self.children
+    }
+
+    /// Copies the tree out of the arena.
+    pub fn to_tree(&self) -> Tree {
+        let children: Vec<Tree> = self.children.iter().map(ArenaTree::to_tree).collect();
+        Tree::new(self.root, List::from(children))
+    }
+}
+
+impl fmt::Display for ArenaTree<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.children.is_empty() {
+            write!(fmt, "{}", self.root)
+        } else {
+            write!(fmt, "{}[", self.root)?;
+            for (n, child) in self.children.iter().enumerate() {
+                if n > 0 {
+                    write!(fmt, " ")?;
+                }
+                write!(fmt, "{}", child)?;
+            }
+            write!(fmt, "]")
+        }
+    }
+}
+
+/// Like `extract_tree`, but allocates the trees and all
+/// intermediate lists in `arena`, so they are freed at once.
+///
+/// The `List`s of `extract_tree` can't be allocated in an arena, so
+/// this mirrors its `yield_tree` and `yield_children` with `ArenaList`s;
+/// a change to one has to be made to the other.
+pub fn extract_tree_in<'t>(
+    arena: &'t Arena,
+    chart: &Chart<'t>,
+    cat: &'t str,
+) -> ArenaList<'t, ArenaTree<'t>> {
+    let end = chart.chart.len() - 1;
+    let topdowns = topdown_map(chart);
+    yield_tree(arena, &topdowns, cat, 0, &|e| e == end).fmap(arena, |(tree, _end)| *tree)
+}
+
+fn yield_tree<'t>(
+    arena: &'t Arena,
+    topdowns: &TopdownMap<'t>,
+    lhs: &'t str,
+    start: usize,
+    test_end: &dyn Fn(usize) -> bool,
+) -> ArenaList<'t, (ArenaTree<'t>, usize)> {
+    let trees = topdowns.get_all(&(lhs, start)).map(|edge| {
+        if test_end(edge.end) {
+            yield_children(arena, topdowns, &edge.rhs, 0, start, edge.end)
+                .fmap(arena, |children| (ArenaTree::new(lhs, *children), edge.end))
+        } else {
+            ArenaList::new()
+        }
+    });
+    ArenaList::concat(arena, trees)
+}
+
+fn yield_children<'t>(
+    arena: &'t Arena,
+    topdowns: &TopdownMap<'t>,
+    rhs: &[&'t str],
+    dot: usize,
+    start: usize,
+    end: usize,
+) -> ArenaList<'t, ArenaList<'t, ArenaTree<'t>>> {
+    if rhs.is_empty() || (start == end && dot == rhs.len()) {
+        ArenaList::from_value(arena, ArenaList::new())
+    } else if start <= end && dot < rhs.len() {
+        let trees = if dot == rhs.len() - 1 {
+            yield_tree(arena, topdowns, rhs[dot], start, &|e| e == end)
+        } else if rhs[dot + 1..].iter().all(|x| is_empty_at(topdowns, x, end)) {
+            yield_tree(arena, topdowns, rhs[dot], start, &|e| e <= end)
+        } else {
+            yield_tree(arena, topdowns, rhs[dot], start, &|e| e < end)
+        };
+        trees.and_then(arena, |(tree, mid)| {
+            yield_children(arena, topdowns, rhs, dot + 1, *mid, end)
+                .fmap(arena, |trees| trees.pushed_front(arena, *tree))
+        })
+    } else {
+        ArenaList::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Rule;
+    use crate::domain::services::chart_parsing::{earley, extract_tree};
+
+    fn rule(lhs: &str, rhs: &[&str]) -> Rule {
+        Rule::new(lhs, rhs.iter().map(|x| x.to_string()).collect())
+    }
+
+    #[test]
+    fn extract_tree_in_agrees_with_extract_tree() {
+        let grammar = vec!(
+            rule("S", &["NP", "VP"]),
+            rule("VP", &["Verb", "NP"]),
+            rule("VP", &["VP", "PP"]),
+            rule("NP", &["NP", "PP"]),
+            rule("NP", &["Det", "Noun"]),
+            rule("PP", &["Prep", "NP"]),
+            rule("Det", &["a"]),
+            rule("Noun", &["lion"]),
+            rule("Noun", &["zebra"]),
+            rule("Noun", &["tree"]),
+            rule("Verb", &["sees"]),
+            rule("Prep", &["under"]),
+        );
+        let sentence = ["a", "lion", "sees", "a", "zebra", "under", "a", "tree"];
+        let arena = Arena::new();
+
+        let chart = earley(&grammar, &sentence);
+        let trees = extract_tree_in(&arena, &chart, "S");
+        let expected = extract_tree(earley(&grammar, &sentence), "S");
+
+        assert_eq!(trees.len(), 2);
+        let mut found: Vec<String> = trees.iter().map(|tree| tree.to_string()).collect();
+        let mut expected: Vec<String> = expected.iter().map(|tree| tree.to_string()).collect();
+        found.sort();
+        expected.sort();
+        assert_eq!(found, expected);
+        assert!(trees.iter().all(|tree| expected.contains(&tree.to_tree().to_string())));
+    }
+}
@@ -1,13 +1,13 @@
 use std::collections::{HashMap, HashSet};
-// use async_stream::stream;
-// use futures::stream::Stream;
-// use futures::pin_mut;
-// use futures::stream::StreamExt;
+use std::iter;
+use async_stream::stream;
+use futures::stream::Stream;
 
-use ds_13::unsync::rb_map::RBMap;
-use ds_13::unsync::list::{List};
+use ds_13::unsync::rb_multimap::RBMultiMap;
+use ds_13::unsync::list::{mbind, List};
+use ds_13::traits::Functor;
 use crate::domain::models::{Rule, Edge, Chart};
-use crate::utilities::container::leftcorners_dict;
+use crate::utilities::container::{leftcorners_dict, nullable_set, rules_dict};
 
 pub fn parse<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> List<Tree> {
     let chart = earley(grammar, sentence);
@@ -16,27 +16,38 @@ pub fn parse<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> Lis
 
 pub fn earley<'a>(grammar: &'a [Rule], input: &[&'a str]) -> Chart<'a> {
     let leftcorners = leftcorners_dict(grammar);
+    let nullable = nullable_set(grammar);
+    let empty_rules: Vec<&Rule> = grammar.iter()
+        .filter(|rule| rule.rhs.iter().all(|x| nullable.contains(x.as_str())))
+        .collect();
 
     let mut chart: Vec<HashMap<Option<&str>, HashSet<Edge>>> = Vec::new();
-    {
-        let mut entry_0 = HashMap::new();
-        entry_0.insert(None, HashSet::new());
-        chart.push(entry_0);
-    }
-
-    for (k, sym) in input.iter().enumerate() {
-        let k = k + 1;
 
+    for k in 0..=input.len() {
         let mut lc_edgesets = HashMap::new();
+        lc_edgesets.insert(None, HashSet::new());
+
+        // Empty constituents
+        let mut agenda: Vec<Edge> = empty_rules.iter()
+            .map(|rule| Edge {
+                start: k,
+                end: k,
+                lhs: &rule.lhs,
+                rhs: rule.rhs.iter().map(String::as_str).collect(),
+                dot: rule.rhs.len(),
+            })
+            .collect();
 
         // Scan
-        let mut agenda = vec!(Edge {
-            start: k-1,
-            end: k,
-            lhs: sym,
-            rhs: Vec::new(),
-            dot: 0,
-        });
+        if k > 0 {
+            agenda.push(Edge {
+                start: k-1,
+                end: k,
+                lhs: input[k-1],
+                rhs: Vec::new(),
+                dot: 0,
+            });
+        }
 
         while agenda.len() > 0 {
             // println!("agenda = {:?}", agenda);
@@ -70,8 +81,8 @@ pub fn earley<'a>(grammar: &'a [Rule], input: &[&'a str]) -> Chart<'a> {
                         }
                     }
 
-                    // Complete
-                    if chart[edge.start].contains_key(&Some(edge.lhs)) {
+                    // Complete, empty constituents are skipped below instead
+                    if edge.start < k && chart[edge.start].contains_key(&Some(edge.lhs)) {
                         for e in &chart[edge.start][&Some(edge.lhs)] {
                             agenda.push(
                                 Edge {
@@ -84,13 +95,124 @@ pub fn earley<'a>(grammar: &'a [Rule], input: &[&'a str]) -> Chart<'a> {
                             );
                         }
                     }
-                } // if edge is passive
+                } else if nullable.contains(edge.rhs[edge.dot]) {
+                    // Skip a nullable symbol (Aycock & Horspool)
+                    agenda.push(
+                        Edge {
+                            start: edge.start,
+                            end: k,
+                            lhs: edge.lhs,
+                            rhs: edge.rhs.clone(),
+                            dot: edge.dot + 1,
+                        }
+                    );
+                }
+                edgeset.insert(edge);
+            } // if edge not in edgeset
+        } // while agenda
+        chart.push(lc_edgesets);
+    } // for input
+
+    passive_chart(chart)
+}
+
+/// Classic Earley parsing, predicting top-down from the rules for
+/// `cat`, so only constituents that can continue a parse of `cat`
+/// from the start of the input end up in the chart.
+pub fn earley_topdown<'a>(grammar: &'a [Rule], cat: &'a str, input: &[&'a str]) -> Chart<'a> {
+    let rules = rules_dict(grammar);
+    let nullable = nullable_set(grammar);
+
+    let mut chart: Vec<HashMap<Option<&str>, HashSet<Edge>>> = Vec::new();
+
+    for k in 0..=input.len() {
+        let mut lc_edgesets = HashMap::new();
+        lc_edgesets.insert(None, HashSet::new());
+
+        let mut agenda: Vec<Edge> = Vec::new();
+        if k == 0 {
+            agenda.extend(predictions(&rules, cat, k));
+        } else {
+            // Scan
+            agenda.push(Edge {
+                start: k-1,
+                end: k,
+                lhs: input[k-1],
+                rhs: Vec::new(),
+                dot: 0,
+            });
+        }
+
+        while let Some(edge) = agenda.pop() {
+            let next = match edge.is_passive() {
+                true => None,
+                false => Some(edge.rhs[edge.dot])
+            };
+            let edgeset = lc_edgesets.entry(next).or_insert(HashSet::<Edge>::new());
+
+            if !edgeset.contains(&edge) {
+                match next {
+                    None => {
+                        // Complete, empty constituents are skipped below instead
+                        if edge.start < k && chart[edge.start].contains_key(&Some(edge.lhs)) {
+                            for e in &chart[edge.start][&Some(edge.lhs)] {
+                                agenda.push(
+                                    Edge {
+                                        start: e.start,
+                                        end: k,
+                                        lhs: e.lhs,
+                                        rhs: e.rhs.clone(),
+                                        dot: e.dot + 1,
+                                    }
+                                );
+                            }
+                        }
+                    }
+                    Some(next) => {
+                        // Predict
+                        agenda.extend(predictions(&rules, next, k));
+
+                        // Skip a nullable symbol (Aycock & Horspool)
+                        if nullable.contains(next) {
+                            agenda.push(
+                                Edge {
+                                    start: edge.start,
+                                    end: k,
+                                    lhs: edge.lhs,
+                                    rhs: edge.rhs.clone(),
+                                    dot: edge.dot + 1,
+                                }
+                            );
+                        }
+                    }
+                }
                 edgeset.insert(edge);
             } // if edge not in edgeset
         } // while agenda
         chart.push(lc_edgesets);
     } // for input
 
+    passive_chart(chart)
+}
+
+/// Returns the edges that start predicting `cat` at position `k`.
+fn predictions<'a>(
+    rules: &HashMap<&'a str, Vec<&'a Rule>>,
+    cat: &str,
+    k: usize,
+) -> impl Iterator<Item = Edge<'a>> + 'a {
+    let rules = rules.get(cat).cloned().unwrap_or_default();
+    rules.into_iter().map(move |rule| Edge {
+        start: k,
+        end: k,
+        lhs: &rule.lhs,
+        rhs: rule.rhs.iter().map(String::as_str).collect(),
+        dot: 0,
+    })
+}
+
+/// Keeps the passive edges of every position.
+fn passive_chart<'a>(chart: Vec<HashMap<Option<&'a str>, HashSet<Edge<'a>>>>) -> Chart<'a> {
     let mut result = Chart::new();
     for lc_edgeset in chart {
         let mut part = Vec::new();
@@ -102,22 +224,41 @@ pub fn earley<'a>(grammar: &'a [Rule], input: &[&'a str]) -> Chart<'a> {
     result
 }
 
+/// The chart parsing algorithms, which build the same kind of chart
+/// for `extract_tree`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// Bottom-up left-corner parsing, see `earley`.
+    #[default]
+    LeftCorner,
+    /// Top-down predictive parsing, see `earley_topdown`.
+    TopDown,
+}
+
+impl Algorithm {
+    pub fn chart<'a>(self, grammar: &'a [Rule], cat: &'a str, input: &[&'a str]) -> Chart<'a> {
+        match self {
+            Algorithm::LeftCorner => earley(grammar, input),
+            Algorithm::TopDown => earley_topdown(grammar, cat, input),
+        }
+    }
+}
+
+/// Like `parse`, but builds the chart with `algorithm`.
+pub fn parse_with<'a>(
+    algorithm: Algorithm,
+    grammar: &'a [Rule],
+    cat: &'a str,
+    sentence: &[&'a str],
+) -> List<Tree> {
+    let chart = algorithm.chart(grammar, cat, sentence);
+    extract_tree(chart, cat)
+}
+
 pub fn extract_tree<'a>(chart: Chart<'a>, cat: &'a str) -> List<Tree> {
     let start: usize = 0;
     let end = chart.chart.len() - 1;
-    let topdowns = {
-        let mut topdowns = RBMap::new();
-        let empty_list = List::new();
-        for edgeset in chart.chart.iter() {
-            for edge in edgeset {
-                if edge.is_passive() {
-                    let edges = topdowns.get_or_default(&(edge.lhs, edge.start), &empty_list).pushed_front(edge.clone());
-                    topdowns = topdowns.inserted_or_replaced((edge.lhs, edge.start), edges);
-                }
-            }
-        }
-        topdowns
-    };
+    let topdowns = topdown_map(&chart);
     let mut result = List::new();
     for (tree, _) in &yield_tree(topdowns.clone(), cat, start, Box::new(move |e| e == end)) {
         result = result.pushed_front(tree.clone());
@@ -126,8 +267,8 @@ pub fn extract_tree<'a>(chart: Chart<'a>, cat: &'a str) -> List<Tree> {
 }
 
 fn yield_tree<'a>(topdowns: TopdownMap<'a>, lhs: &'a str, start: usize, test_end: Box<dyn Fn(usize) -> bool>) -> List<(Tree, usize)> {
-    let mut result = List::new();
-    for edge in topdowns.get_or_default(&(lhs, start), &List::new()) {
+    let edges = topdowns.get(&(lhs, start)).cloned().unwrap_or_else(List::new);
+    mbind(&edges, |edge| {
         if test_end(edge.end) {
             let yield_children = yield_children(
                 topdowns.clone(),
@@ -136,12 +277,11 @@ fn yield_tree<'a>(topdowns: TopdownMap<'a>, lhs: &'a str, start: usize, test_end
                 start,
                 edge.end
             );
-            for children in &yield_children {
-                result = result.pushed_front((Tree::new(lhs, children.clone()), edge.end));
-            }
+            yield_children.fmap(|children| (Tree::new(lhs, children.clone()), edge.end))
+        } else {
+            List::new()
         }
-    }
-    result
+    })
 }
 
 fn yield_children<'a>(topdowns: TopdownMap<'a>, rhs: Vec<&'a str>, dot: usize, start: usize, end: usize) -> List<List<Tree>> {
@@ -150,7 +290,7 @@ fn yield_children<'a>(topdowns: TopdownMap<'a>, rhs: Vec<&'a str>, dot: usize, s
         result = result.pushed_front(List::new());
     } else if start == end && dot == rhs.len() {
         result = result.pushed_front(List::new());
-    } else if start < end && dot < rhs.len() {
+    } else if start <= end && dot < rhs.len() {
         let yield_tree = if dot == rhs.len() - 1 {
             yield_tree(
                 topdowns.clone(),
@@ -158,6 +298,14 @@ fn yield_children<'a>(topdowns: TopdownMap<'a>, rhs: Vec<&'a str>, dot: usize, s
                 start,
                 Box::new(move |e| e == end)
             )
+        } else if rhs[dot + 1..].iter().all(|x| is_empty_at(&topdowns, x, end)) {
+            // The rest can be empty constituents, so this child may end at `end`
+            yield_tree(
+                topdowns.clone(),
+                rhs[dot],
+                start,
+                Box::new(move |e| e <= end)
+            )
         } else {
             yield_tree(
                 topdowns.clone(),
@@ -166,7 +314,7 @@ fn yield_children<'a>(topdowns: TopdownMap<'a>, rhs: Vec<&'a str>, dot: usize, s
                 Box::new(move |e| e < end)
             )
         };
-        for (tree, mid) in &yield_tree {
+        result = mbind(&yield_tree, |(tree, mid)| {
             let yield_children = yield_children(
                 topdowns.clone(),
                 rhs.clone(),
@@ -174,177 +322,130 @@ fn yield_children<'a>(topdowns: TopdownMap<'a>, rhs: Vec<&'a str>, dot: usize, s
                 *mid,
                 end
             );
-            for trees in &yield_children {
-                result = result.pushed_front(List::cons(tree.clone(), &trees));
+            yield_children.fmap(|trees| List::cons(tree.clone(), trees))
+        });
+    }
+    result
+}
+
+pub(crate) type TopdownMap<'a> = RBMultiMap<(&'a str, usize), Edge<'a>>;
+
+/// Returns `true` if `lhs` has an empty constituent at position `k`.
+pub(crate) fn is_empty_at(topdowns: &TopdownMap, lhs: &str, k: usize) -> bool {
+    topdowns.get_all(&(lhs, k)).any(|edge| edge.end == k)
+}
+
+/// Returns the passive edges of the chart by their lhs and start.
+pub(crate) fn topdown_map<'a>(chart: &Chart<'a>) -> TopdownMap<'a> {
+    let mut topdowns = RBMultiMap::new();
+    for edgeset in chart.chart.iter() {
+        for edge in edgeset {
+            if edge.is_passive() {
+                topdowns = topdowns.pushed((edge.lhs, edge.start), edge.clone());
             }
         }
     }
-    result
+    topdowns
 }
 
-type TopdownMap<'a> = RBMap<(&'a str, usize), List<Edge<'a>>>;
-
-// pub struct ParseTreeIterator<'a> {
-//     chart: Chart<'a>,
-//     cat: &'a str,
-//     topdowns: TopdownMap<'a>,
-//     yield_tree: YieldTreeIterator<'a>,
-// }
-
-// impl<'a> ParseTreeIterator<'a> {
-//     fn new(chart: Chart<'a>, cat: &'a str) -> Self {
-//         let topdowns = {
-//             let mut topdowns = RBMap::new();
-//             let empty_list = List::new();
-//             for edgeset in chart.chart.iter() {
-//                 for edge in edgeset {
-//                     if edge.is_passive() {
-//                         let edges = topdowns.get_or_default(&(edge.lhs, edge.start), &empty_list).pushed_front(edge.clone());
-//                         topdowns = topdowns.inserted_or_replaced((edge.lhs, edge.start), edges);
-//                     }
-//                 }
-//             }
-//             topdowns
-//         };
-//         let end = chart.chart.len() - 1;
-//         let yield_tree = YieldTreeIterator::new(topdowns.clone(), cat, 0, Box::new(move |e| e == end) );
-//         ParseTreeIterator { chart, cat, topdowns, yield_tree }
-//     }
-// }
-
-// impl<'a> Iterator for ParseTreeIterator<'a> {
-//     type Item = Tree;
-
-//     fn next(&mut self) -> Option<Self::Item> {
-//         println!("ParseTreeIterator::next");
-//         match self.yield_tree.next() {
-//             None => None,
-//             Some(tree) => Some(tree),
-//         }
-//     }
-// }
-
-// struct YieldTreeIterator<'a> {
-//     topdowns: TopdownMap<'a>,
-//     lhs: &'a str,
-//     start: usize,
-//     test_end: Box<dyn Fn(usize) -> bool>,
-//     edge_iter: Box<dyn Iterator<Item = &'a Edge<'a>>>,
-//     yield_children: YieldChildrenIterator<'a>,
-//     edge: Option<Edge<'a>>,
-// }
-
-// impl<'a> YieldTreeIterator<'a> {
-//     fn new(topdowns: TopdownMap<'a>, lhs: &'a str, start: usize, test_end: Box<dyn Fn(usize) -> bool>) -> Self {
-//         let yield_children = YieldChildrenIterator::empty();
-//         let edge_iter = Box::new(topdowns.get_or_default(&(lhs, start), &List::new()).into_iter());
-//         let edge = None;
-//         YieldTreeIterator { topdowns, lhs, start, test_end, edge_iter, yield_children, edge }
-
-//     }
-// }
-
-// impl<'a> Iterator for YieldTreeIterator<'a> {
-//     type Item = Tree;
-
-//     fn next(&mut self) -> Option<Self::Item> {
-//         println!("YieldTreeIterator::next");
-//         if self.yield_children.is_empty() {
-//             loop {
-//                 match &self.edge_iter.next() {
-//                     None => { return None; },
-//                     Some(edge) => {
-//                         println!("{:?}", edge);
-//                         if (self.test_end)(edge.end) {
-//                             self.edge = Some(edge.clone());
-//                             self.yield_children = YieldChildrenIterator::new(
-//                                 self.topdowns.clone(),
-//                                 edge.rhs.clone(),
-//                                 0,
-//                                 self.start,
-//                                 edge.end
-//                             );
-//                             break;
-//                         }
-//                     }
-//                 }
-//             }
-//         }
-//         if !self.yield_children.is_empty() {
-//             match self.yield_children.next() {
-//                 None => (),
-//                 Some(children) => {
-//                     return Some(Tree::new(self.lhs, children));
-//                 }
-//             }
-//         }
-//         None
-//     }
-// }
-
-// struct YieldChildrenIterator<'a> {
-//     rhs: Option<Vec<&'a str>>,
-//     //yield_tree: YieldTreeIterator<'a>,
-//     mid: usize,
-//     tree: Option<Tree>,
-
-// }
-
-// impl<'a> YieldChildrenIterator<'a> {
-//     fn empty() -> Self {
-//         YieldChildrenIterator {
-//             rhs: None,
-//             //yield_tree: YieldTreeIterator<'a>,
-//             mid: 0,
-//             tree: None,
-//         }
-//     }
-
-//     fn new(topdowns: TopdownMap<'a>, rhs: Vec<&'a str>, dot: usize, start: usize, end: usize) -> Self {
-//         let yield_tree = if dot == rhs.len() - 1 {
-//             YieldTreeIterator::new(
-//                 topdowns.clone(),
-//                 rhs[dot],
-//                 start,
-//                 Box::new( move |e| e == end )
-//             )
-//         } else {
-//             YieldTreeIterator::new(
-//                 topdowns.clone(),
-//                 rhs[dot],
-//                 start,
-//                 Box::new( move |e| e < end )
-//             )
-//         };
-//         YieldChildrenIterator {
-//             rhs: Some(rhs),
-//             //yield_tree: yield_tree,
-//             mid: 0,
-//             tree: None,
-//         }
-//     }
-
-//     fn is_empty(&self) -> bool {
-//         match &self.rhs {
-//             None => true,
-//             _ => false,
-//         }
-//     }
-// }
-
-// impl<'a> Iterator for YieldChildrenIterator<'a> {
-//     type Item = List<Tree>;
-
-//     fn next(&mut self) -> Option<Self::Item> {
-//         println!("YieldChildrenIterator::next");
-// //        match self.yield_tree.next() {
-// //            None => None,
-// //            Some(tree) => List::cons(tree, List::new()),
-// //        }
-//         None
-//     }
-// }
-#[derive(Clone, Debug)]
+/// Yields the parse trees of a chart lazily, like `extract_tree`, but
+/// only building the next tree when it is asked for.
+///
+/// # Examples
+///
+/// ```
+/// use kxparser::domain::models::Grammar;
+/// use kxparser::domain::services::chart_parsing::parse_trees;
+///
+/// let grammar: Grammar = "S --> S S | a".parse().unwrap();
+///
+/// // There are 1 767 263 190 trees
+/// let trees: Vec<_> = parse_trees(&grammar.rules, "S", &["a"; 20]).take(3).collect();
+///
+/// assert_eq!(trees.len(), 3);
+/// ```
+pub struct ParseTreeIterator<'a> {
+    trees: Box<dyn Iterator<Item = (Tree, usize)> + 'a>,
+}
+
+impl<'a> ParseTreeIterator<'a> {
+    pub fn new(chart: &Chart<'a>, cat: &'a str) -> Self {
+        let end = chart.chart.len() - 1;
+        let trees = lazy_yield_tree(topdown_map(chart), cat, 0, Box::new(move |e| e == end));
+        ParseTreeIterator { trees }
+    }
+}
+
+impl Iterator for ParseTreeIterator<'_> {
+    type Item = Tree;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.trees.next().map(|(tree, _end)| tree)
+    }
+}
+
+/// Parses with `earley` and returns the trees lazily.
+pub fn parse_trees<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> ParseTreeIterator<'a> {
+    let chart = earley(grammar, sentence);
+    ParseTreeIterator::new(&chart, cat)
+}
+
+/// Returns the parse trees as a stream, which parses when it is first
+/// polled and then builds one tree per item.
+pub fn parse_tree_stream<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> impl Stream<Item = Tree> + 'a {
+    let sentence = sentence.to_vec();
+    stream! {
+        for tree in parse_trees(grammar, cat, &sentence) {
+            yield tree;
+        }
+    }
+}
+
+fn lazy_yield_tree<'a>(
+    topdowns: TopdownMap<'a>,
+    lhs: &'a str,
+    start: usize,
+    test_end: Box<dyn Fn(usize) -> bool + 'a>,
+) -> Box<dyn Iterator<Item = (Tree, usize)> + 'a> {
+    let edges: Vec<Edge<'a>> = topdowns.get_all(&(lhs, start))
+        .filter(|edge| test_end(edge.end))
+        .cloned()
+        .collect();
+    Box::new(edges.into_iter().flat_map(move |edge| {
+        let end = edge.end;
+        lazy_yield_children(topdowns.clone(), edge.rhs, 0, start, end)
+            .map(move |children| (Tree::new(lhs, children), end))
+    }))
+}
+
+fn lazy_yield_children<'a>(
+    topdowns: TopdownMap<'a>,
+    rhs: Vec<&'a str>,
+    dot: usize,
+    start: usize,
+    end: usize,
+) -> Box<dyn Iterator<Item = List<Tree>> + 'a> {
+    if rhs.is_empty() || (start == end && dot == rhs.len()) {
+        Box::new(iter::once(List::new()))
+    } else if start <= end && dot < rhs.len() {
+        let test_end: Box<dyn Fn(usize) -> bool> = if dot == rhs.len() - 1 {
+            Box::new(move |e| e == end)
+        } else if rhs[dot + 1..].iter().all(|x| is_empty_at(&topdowns, x, end)) {
+            Box::new(move |e| e <= end)
+        } else {
+            Box::new(move |e| e < end)
+        };
+        let trees = lazy_yield_tree(topdowns.clone(), rhs[dot], start, test_end);
+        Box::new(trees.flat_map(move |(tree, mid)| {
+            lazy_yield_children(topdowns.clone(), rhs.clone(), dot + 1, mid, end)
+                .map(move |trees| List::cons(tree.clone(), &trees))
+        }))
+    } else {
+        Box::new(iter::empty())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Tree {
     root: String,
     children: List<Tree>,
@@ -364,6 +465,14 @@ impl Tree {
             children: List::new()
         }
     }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub fn children(&self) -> &List<Tree> {
+        &self.children
+    }
 }
 
 impl std::fmt::Display for Tree {
@@ -385,3 +494,150 @@ impl std::fmt::Display for Tree {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Grammar;
+
+    fn parses(grammar: &str, sentence: &str) -> Vec<String> {
+        let grammar: Grammar = grammar.parse().unwrap();
+        let sentence: Vec<&str> = sentence.split_whitespace().collect();
+        let trees = sorted(parse(&grammar.rules, "S", &sentence));
+        assert_eq!(sorted(parse_with(Algorithm::TopDown, &grammar.rules, "S", &sentence)), trees);
+        trees
+    }
+
+    fn sorted(trees: List<Tree>) -> Vec<String> {
+        let mut trees: Vec<String> = trees.iter().map(|tree| tree.to_string()).collect();
+        trees.sort();
+        trees
+    }
+
+    #[test]
+    fn parses_without_empty_rules() {
+        assert_eq!(
+            parses("S --> NP VP\nNP --> John | Mary\nVP --> V NP\nV --> sees", "John sees Mary"),
+            vec!("S[NP[John] VP[V[sees] NP[Mary]]]")
+        );
+        assert!(parses("S --> a", "a a").is_empty());
+    }
+
+    #[test]
+    fn parses_empty_constituents() {
+        let grammar = "
+            S --> NP VP
+            NP --> Det Adj N
+            Det --> the |
+            Adj --> big |
+            N --> dog
+            VP --> barks
+        ";
+
+        assert_eq!(parses(grammar, "dog barks"), vec!("S[NP[Det Adj N[dog]] VP[barks]]"));
+        assert_eq!(parses(grammar, "the big dog barks"), vec!("S[NP[Det[the] Adj[big] N[dog]] VP[barks]]"));
+        assert!(parses(grammar, "the the dog barks").is_empty());
+    }
+
+    #[test]
+    fn parses_nullable_chains() {
+        let grammar = "
+            S --> A x B
+            A --> B C
+            B --> C
+            C -->
+        ";
+
+        assert_eq!(parses(grammar, "x"), vec!("S[A[B[C] C] x B[C]]"));
+        assert!(parses(grammar, "").is_empty());
+    }
+
+    #[test]
+    fn parses_empty_sentence() {
+        assert_eq!(parses("S --> A B\nA -->\nB --> A", ""), vec!("S[A B[A]]"));
+    }
+
+    #[test]
+    fn empty_constituents_can_be_ambiguous() {
+        assert_eq!(parses("S --> A A\nA --> a |", "a"), vec!("S[A A[a]]", "S[A[a] A]"));
+        assert_eq!(parses("S --> A A\nA --> a |", "a a"), vec!("S[A[a] A[a]]"));
+    }
+
+    #[test]
+    fn topdown_chart_is_filtered() {
+        let grammar: Grammar = "
+            S --> NP VP
+            NP --> Det N | NP PP
+            VP --> V NP | VP PP
+            PP --> P NP
+            Det --> a | the
+            N --> lion | saw | park
+            V --> saw
+            P --> in
+        ".parse().unwrap();
+        let sentence = ["a", "lion", "saw", "a", "lion", "in", "the", "park"];
+
+        let leftcorner = earley(&grammar.rules, &sentence);
+        let topdown = earley_topdown(&grammar.rules, "S", &sentence);
+
+        // `saw` is only predicted as a verb
+        assert!(leftcorner.chart[3].iter().any(|edge| edge.lhs == "N"));
+        assert!(!topdown.chart[3].iter().any(|edge| edge.lhs == "N"));
+        assert!(topdown.chartsize() < leftcorner.chartsize());
+        assert_eq!(
+            sorted(extract_tree(topdown, "S")),
+            sorted(extract_tree(leftcorner, "S"))
+        );
+        assert_eq!(Algorithm::default(), Algorithm::LeftCorner);
+    }
+
+    #[test]
+    fn parse_trees_agrees_with_parse() {
+        let grammar: Grammar = "
+            S --> NP VP
+            NP --> Det N | NP PP
+            VP --> V NP | VP PP
+            PP --> P NP
+            Det --> a |
+            N --> lion | zebra | tree
+            V --> sees
+            P --> under | in
+        ".parse().unwrap();
+        let sentence = ["a", "lion", "sees", "zebra", "under", "a", "tree", "in", "a", "tree"];
+
+        let trees = sorted(parse(&grammar.rules, "S", &sentence));
+
+        assert_eq!(trees.len(), 5);
+        assert_eq!(sorted(List::from(parse_trees(&grammar.rules, "S", &sentence).collect::<Vec<_>>())), trees);
+    }
+
+    #[test]
+    fn parse_trees_is_lazy() {
+        let grammar: Grammar = "S --> NP VP\nNP --> NP PP | a\nVP --> VP PP | b\nPP --> c NP".parse().unwrap();
+        let mut sentence = vec!("a", "b");
+        for _ in 0..30 {
+            sentence.extend(&["c", "a"]);
+        }
+
+        let mut trees = parse_trees(&grammar.rules, "S", &sentence);
+
+        // There are C(31) = 14 544 636 039 226 909 trees
+        assert!(trees.next().is_some());
+        assert_eq!(trees.take(99).count(), 99);
+    }
+
+    #[test]
+    fn parse_tree_stream_yields_trees() {
+        use futures::executor::block_on;
+        use futures::stream::StreamExt;
+
+        let grammar: Grammar = "S --> S S | a".parse().unwrap();
+
+        let stream = parse_tree_stream(&grammar.rules, "S", &["a"; 4]);
+        let trees: Vec<Tree> = block_on(stream.collect());
+        let first: Vec<Tree> = block_on(parse_tree_stream(&grammar.rules, "S", &["a"; 30]).take(2).collect());
+
+        assert_eq!(trees.len(), 5);
+        assert_eq!(first.len(), 2);
+    }
+}
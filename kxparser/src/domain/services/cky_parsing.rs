@@ -0,0 +1,215 @@
+use std::collections::{HashMap, HashSet};
+
+use ds_13::unsync::list::List;
+use crate::domain::models::{Rule, Edge, Chart, CnfGrammar};
+use crate::domain::models::cnf_grammar::Fragment;
+use crate::domain::services::chart_parsing::{extract_tree, Tree};
+
+/// Parses with `cky` and maps the trees back to the original grammar.
+pub fn parse_cky<'a>(grammar: &'a CnfGrammar, cat: &'a str, sentence: &[&'a str]) -> List<Tree> {
+    let chart = cky(grammar, sentence);
+    let trees: Vec<Tree> = extract_tree(chart, cat)
+        .iter()
+        .flat_map(|tree| original_trees(grammar, tree))
+        .collect();
+    List::from(trees)
+}
+
+/// CKY parsing, which fills the chart with the passive edges of every
+/// span, shortest spans first.
+pub fn cky<'a>(grammar: &'a CnfGrammar, input: &[&'a str]) -> Chart<'a> {
+    let mut lexical: HashMap<&str, Vec<&Rule>> = HashMap::new();
+    let mut binary: HashMap<(&str, &str), Vec<&Rule>> = HashMap::new();
+    for rule in &grammar.grammar.rules {
+        match rule.rhs.as_slice() {
+            [word] => lexical.entry(word.as_str()).or_default().push(rule),
+            [left, right] => binary.entry((left.as_str(), right.as_str())).or_default().push(rule),
+            _ => (),
+        }
+    }
+
+    let n = input.len();
+    // cells[i][j] holds the lhs of the constituents from i to j
+    let mut cells: Vec<Vec<HashSet<&str>>> = vec!(vec!(HashSet::new(); n + 1); n + 1);
+    let mut chart: Vec<HashSet<Edge>> = vec!(HashSet::new(); n + 1);
+
+    if n == 0 {
+        for rule in grammar.grammar.rules.iter().filter(|rule| rule.rhs.is_empty()) {
+            chart[0].insert(Edge::new(0, 0, &rule.lhs, None, 0));
+        }
+    }
+
+    for j in 1..=n {
+        let word = input[j-1];
+        chart[j].insert(Edge::new(j-1, j, word, None, 0));
+        for rule in lexical.get(word).into_iter().flatten() {
+            cells[j-1][j].insert(&rule.lhs);
+            chart[j].insert(Edge::new(j-1, j, &rule.lhs, Some(&[word]), 1));
+        }
+
+        for i in (0..j-1).rev() {
+            let mut found = Vec::new();
+            for (mid, row) in cells.iter().enumerate().take(j).skip(i+1) {
+                for left in &cells[i][mid] {
+                    for right in &row[j] {
+                        for rule in binary.get(&(*left, *right)).into_iter().flatten() {
+                            found.push(&rule.lhs);
+                            chart[j].insert(Edge::new(i, j, &rule.lhs, Some(&[left, right]), 2));
+                        }
+                    }
+                }
+            }
+            cells[i][j].extend(found.into_iter().map(String::as_str));
+        }
+    }
+
+    let mut result = Chart::new();
+    for edgeset in chart {
+        result.chart.push(edgeset.into_iter().collect());
+    }
+    result
+}
+
+/// Returns the trees of the original grammar that a tree of the CNF
+/// grammar stands for.
+pub fn original_trees(grammar: &CnfGrammar, tree: &Tree) -> Vec<Tree> {
+    yields(grammar, tree)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Returns the alternative sequences of original trees that `tree`
+/// yields, where a leaf below the root is a word.
+fn yields(grammar: &CnfGrammar, tree: &Tree) -> Vec<Vec<Tree>> {
+    let rhs: Vec<&str> = tree.children().iter().map(Tree::root).collect();
+    let rule = match grammar.position(tree.root(), &rhs) {
+        Some(rule) => rule,
+        None => return Vec::new(),
+    };
+    let children: Vec<Vec<Vec<Tree>>> = tree.children()
+        .iter()
+        .map(|child| {
+            if child.children().is_empty() {
+                vec!(vec!(child.clone()))
+            } else {
+                yields(grammar, child)
+            }
+        })
+        .collect();
+    let choices = children.iter().fold(vec!(Vec::new()), |choices, alternatives| {
+        choices.iter()
+            .flat_map(|choice: &Vec<&Vec<Tree>>| alternatives.iter().map(move |alternative| {
+                let mut choice = choice.clone();
+                choice.push(alternative);
+                choice
+            }))
+            .collect()
+    });
+
+    let mut result = Vec::new();
+    for template in &grammar.provenance[rule] {
+        for choice in &choices {
+            result.push(instantiated(grammar, template, choice));
+        }
+    }
+    result
+}
+
+fn instantiated(grammar: &CnfGrammar, template: &[Fragment], children: &[&Vec<Tree>]) -> Vec<Tree> {
+    let mut result = Vec::new();
+    for fragment in template {
+        match fragment {
+            Fragment::Child(i) => result.extend(children[*i].iter().cloned()),
+            Fragment::Node { rule, children: fragments } => {
+                let lhs = &grammar.original.rules[*rule].lhs;
+                let subtrees = instantiated(grammar, fragments, children);
+                result.push(Tree::new(lhs, List::from(subtrees)));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Grammar;
+    use crate::domain::services::chart_parsing::parse;
+
+    fn agree(grammar: &str, sentence: &str) -> Vec<String> {
+        let grammar: Grammar = grammar.parse().unwrap();
+        let cnf = grammar.to_cnf();
+        let sentence: Vec<&str> = sentence.split_whitespace().collect();
+        let sorted = |trees: List<Tree>| {
+            let mut trees: Vec<String> = trees.iter().map(|tree| tree.to_string()).collect();
+            trees.sort();
+            trees
+        };
+        let trees = sorted(parse_cky(&cnf, "S", &sentence));
+        assert_eq!(trees, sorted(parse(&grammar.rules, "S", &sentence)));
+        trees
+    }
+
+    #[test]
+    fn agrees_with_earley_on_ambiguous_sentences() {
+        let grammar = "
+            S --> NP VP
+            NP --> Det Noun | NP PP | Name
+            VP --> Verb NP | VP PP | sleeps
+            PP --> Prep NP
+            Det --> a | the
+            Noun --> lion | zebra | tree | telescope
+            Name --> Leo
+            Verb --> sees
+            Prep --> under | with
+        ";
+
+        assert_eq!(agree(grammar, "Leo sleeps").len(), 1);
+        assert_eq!(agree(grammar, "a lion sees a zebra under a tree").len(), 2);
+        assert_eq!(agree(grammar, "a lion sees a zebra under a tree with a telescope").len(), 5);
+        assert!(agree(grammar, "a lion sees").is_empty());
+    }
+
+    #[test]
+    fn agrees_with_earley_on_long_and_empty_rules() {
+        let grammar = "
+            S --> NP VP | S and S
+            NP --> Det Adj N
+            Det --> the |
+            Adj --> big |
+            N --> dog
+            VP --> barks
+        ";
+
+        assert_eq!(agree(grammar, "dog barks"), vec!("S[NP[Det Adj N[dog]] VP[barks]]"));
+        assert_eq!(agree(grammar, "the dog barks and big dog barks").len(), 1);
+        assert_eq!(agree("S --> A A\nA --> a |", "a").len(), 2);
+        assert_eq!(agree("S --> A B\nA -->\nB --> A", ""), vec!("S[A B[A]]"));
+    }
+
+    #[test]
+    fn keeps_every_derivation_of_the_empty_string() {
+        let grammar = "S --> A b\nA --> B | C\nB -->\nC -->";
+
+        assert_eq!(agree(grammar, "b"), vec!("S[A[B] b]", "S[A[C] b]"));
+        assert_eq!(agree(grammar, "").len(), 0);
+        assert_eq!(agree("S --> A | B\nA --> B |\nB -->", "").len(), 3);
+    }
+
+    #[test]
+    fn chart_has_edges_of_the_cnf_grammar() {
+        let grammar: Grammar = "S --> a b c".parse().unwrap();
+        let cnf = grammar.to_cnf();
+
+        let chart = cky(&cnf, &["a", "b", "c"]);
+
+        assert_eq!(chart.chart.len(), 4);
+        assert!(chart.chart[3].contains(&Edge::new(0, 3, "S", Some(&["<a>", "<<b> <c>>"]), 2)));
+        assert_eq!(
+            extract_tree(chart, "S").iter().map(|tree| tree.to_string()).collect::<Vec<_>>(),
+            vec!("S[<a>[a] <<b> <c>>[<b>[b] <c>[c]]]")
+        );
+        assert_eq!(parse_cky(&cnf, "S", &["a", "b", "c"]).iter().next().unwrap().to_string(), "S[a b c]");
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+
+use ds_13::unsync::list::List;
+use crate::domain::models::{Rule, Chart};
+use crate::domain::services::chart_parsing::{earley, topdown_map, TopdownMap, Tree};
+
+/// What a forest node stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Label<'a> {
+    /// The constituents of `lhs`, or a word, from `start` to `end`.
+    Symbol { lhs: &'a str, start: usize, end: usize },
+    /// The sequences of constituents for `rhs`, from `start` to `end`,
+    /// shared by all rules whose rhs starts with `rhs`.
+    Sequence { rhs: Vec<&'a str>, start: usize, end: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForestNode<'a> {
+    pub label: Label<'a>,
+    /// The packed alternatives, each the indices of its children. A
+    /// rule with a rhs longer than one symbol has a `Sequence` for the
+    /// rhs without its last symbol as the first child.
+    pub alternatives: Vec<Vec<usize>>,
+}
+
+/// A shared packed parse forest, which holds every constituent once,
+/// with its alternative analyses packed in one node.
+///
+/// Every node comes after its children in `nodes`. Trees where a
+/// constituent contains itself, like `A --> B`, `B --> A` over the
+/// same span, are left out, as in `semiring_parse`. The constituents
+/// on such a cycle get a node for every path from the root to them,
+/// since which of their trees are left out depends on the
+/// constituents above them.
+#[derive(Debug, Clone)]
+pub struct Forest<'a> {
+    nodes: Vec<ForestNode<'a>>,
+    root: Option<usize>,
+    /// The number of trees or sequences of every node, saturating at
+    /// `u64::MAX`. A saturated node stands for its first `u64::MAX`
+    /// trees.
+    counts: Vec<u64>,
+}
+
+/// Parses with `earley` and packs the trees of `cat` in a forest.
+pub fn parse_forest<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> Forest<'a> {
+    let chart = earley(grammar, sentence);
+    Forest::new(&chart, cat)
+}
+
+impl<'a> Forest<'a> {
+    /// Builds the forest of the trees of `cat` over the whole input of
+    /// `chart`, in polynomial time if the grammar has no unit or empty
+    /// cycles, whose nodes are built again wherever they are reached.
+    pub fn new(chart: &Chart<'a>, cat: &'a str) -> Self {
+        let topdowns = topdown_map(chart);
+        let mut builder = Builder {
+            topdowns: &topdowns,
+            nodes: Vec::new(),
+            memo: HashMap::new(),
+            active: HashMap::new(),
+        };
+        let (root, _) = builder.node(Label::Symbol { lhs: cat, start: 0, end: chart.chart.len() - 1 });
+        let nodes = builder.nodes;
+
+        let mut counts: Vec<u64> = Vec::with_capacity(nodes.len());
+        for node in &nodes {
+            let count = node.alternatives.iter()
+                .map(|children| children.iter().fold(1u64, |n, child| n.saturating_mul(counts[*child])))
+                .fold(0u64, u64::saturating_add);
+            counts.push(count);
+        }
+        Forest { nodes, root, counts }
+    }
+
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    pub fn nodes(&self) -> &[ForestNode<'a>] {
+        &self.nodes
+    }
+
+    /// Returns the number of trees, saturating at `u64::MAX`.
+    ///
+    /// Complexity: O(1)
+    pub fn count_trees(&self) -> u64 {
+        self.root.map_or(0, |root| self.counts[root])
+    }
+
+    /// Returns the k-th tree, or `None` if `k` isn't less than
+    /// `count_trees()`, building only that tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kxparser::domain::models::Grammar;
+    /// use kxparser::domain::services::forest::parse_forest;
+    ///
+    /// let grammar: Grammar = "S --> S S | a".parse().unwrap();
+    /// let forest = parse_forest(&grammar.rules, "S", &["a"; 20]);
+    ///
+    /// assert_eq!(forest.count_trees(), 1_767_263_190);
+    /// assert_eq!(forest.tree(0).unwrap().to_string().matches('S').count(), 39);
+    /// assert!(forest.tree(1_767_263_190).is_none());
+    /// ```
+    pub fn tree(&self, k: u64) -> Option<Tree> {
+        match self.root {
+            Some(root) if k < self.counts[root] => self.sequence(root, k).pop(),
+            _ => None,
+        }
+    }
+
+    /// Returns the trees in the order of `tree`, building them on demand.
+    pub fn trees(&self) -> impl Iterator<Item = Tree> + '_ {
+        (0..self.count_trees()).filter_map(move |k| self.tree(k))
+    }
+
+    /// Returns the k-th tree or sequence of trees of a node, where the
+    /// last child varies fastest.
+    fn sequence(&self, id: usize, mut k: u64) -> Vec<Tree> {
+        let node = &self.nodes[id];
+        for alternative in &node.alternatives {
+            let count = alternative.iter().fold(1u64, |n, child| n.saturating_mul(self.counts[*child]));
+            if k >= count {
+                k -= count;
+                continue;
+            }
+            // The digits of `k` with the counts of the children as
+            // radixes, from the last child, which never divides by a
+            // product that may have saturated
+            let mut ks = vec!(0; alternative.len());
+            for (i, child) in alternative.iter().enumerate().rev() {
+                ks[i] = k % self.counts[*child];
+                k /= self.counts[*child];
+            }
+            let mut children = Vec::new();
+            for (child, k) in alternative.iter().zip(ks) {
+                children.extend(self.sequence(*child, k));
+            }
+            return match node.label {
+                Label::Symbol { lhs, .. } => vec!(Tree::new(lhs, List::from(children))),
+                Label::Sequence { .. } => children,
+            };
+        }
+        Vec::new()
+    }
+}
+
+struct Builder<'t, 'a> {
+    topdowns: &'t TopdownMap<'a>,
+    nodes: Vec<ForestNode<'a>>,
+    /// The nodes by label that aren't on a cycle, `None` without trees.
+    memo: HashMap<Label<'a>, Option<usize>>,
+    /// The symbols that are being built, by their depth.
+    active: HashMap<Label<'a>, usize>,
+}
+
+impl<'t, 'a> Builder<'t, 'a> {
+    /// Returns the node, if it has trees, and the least depth of the
+    /// symbols being built that it depends on, which is `usize::MAX`
+    /// if it depends on none of them.
+    fn node(&mut self, label: Label<'a>) -> (Option<usize>, usize) {
+        if let Some(id) = self.memo.get(&label) {
+            return (*id, usize::MAX);
+        }
+        // A cycle, which is left out
+        if let Some(depth) = self.active.get(&label) {
+            return (None, *depth);
+        }
+        let (alternatives, low) = match &label {
+            Label::Symbol { lhs, start, end } => {
+                self.active.insert(label.clone(), self.active.len());
+                let topdowns = self.topdowns;
+                let mut alternatives = Vec::new();
+                let mut low = usize::MAX;
+                for edge in topdowns.get_all(&(*lhs, *start)).filter(|edge| edge.end == *end) {
+                    match edge.rhs.is_empty() {
+                        true => alternatives.push(Vec::new()),
+                        false => {
+                            let (more, depth) = self.alternatives(&edge.rhs, *start, *end);
+                            alternatives.extend(more);
+                            low = low.min(depth);
+                        }
+                    }
+                }
+                (alternatives, low)
+            }
+            Label::Sequence { rhs, start, end } => self.alternatives(rhs, *start, *end),
+        };
+        // Without cycles through this node or the ones above it, the
+        // node is the same wherever it is reached
+        let shared = low >= self.active.len();
+        self.active.remove(&label);
+        let id = match alternatives.is_empty() {
+            true => None,
+            false => {
+                self.nodes.push(ForestNode { label: label.clone(), alternatives });
+                Some(self.nodes.len() - 1)
+            }
+        };
+        if shared {
+            self.memo.insert(label, id);
+        }
+        (id, low)
+    }
+
+    /// Returns the ways to split the span into a node for all but the
+    /// last symbol of `rhs` and a node for the last symbol, and the
+    /// least depth of the symbols being built that they depend on.
+    fn alternatives(&mut self, rhs: &[&'a str], start: usize, end: usize) -> (Vec<Vec<usize>>, usize) {
+        let (last, prefix) = match rhs.split_last() {
+            Some(split) => split,
+            None => return (Vec::new(), usize::MAX),
+        };
+        let mut alternatives = Vec::new();
+        let mut low = usize::MAX;
+        for mid in start..=end {
+            if prefix.is_empty() && mid > start {
+                break;
+            }
+            // A node without trees only depends on the symbols being
+            // built if the other node has trees or depends on them too,
+            // so that left recursion that can't be completed isn't
+            // taken for a cycle
+            let (last, last_low) = self.node(Label::Symbol { lhs: last, start: mid, end });
+            if last.is_none() && last_low == usize::MAX {
+                continue;
+            }
+            if prefix.is_empty() {
+                low = low.min(last_low);
+                alternatives.extend(last.map(|last| vec!(last)));
+                continue;
+            }
+            let (prefix, prefix_low) = match prefix {
+                [symbol] => self.node(Label::Symbol { lhs: symbol, start, end: mid }),
+                _ => self.node(Label::Sequence { rhs: prefix.to_vec(), start, end: mid }),
+            };
+            if prefix.is_none() && prefix_low == usize::MAX {
+                continue;
+            }
+            low = low.min(last_low).min(prefix_low);
+            if let (Some(prefix), Some(last)) = (prefix, last) {
+                alternatives.push(vec!(prefix, last));
+            }
+        }
+        (alternatives, low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Grammar;
+    use crate::domain::services::chart_parsing::parse;
+    use crate::domain::services::semiring_parsing::count_trees;
+
+    const GRAMMAR: &str = "
+        S --> NP VP
+        NP --> Det Noun | NP PP
+        VP --> Verb NP | VP PP
+        PP --> Prep NP
+        Det --> a
+        Noun --> lion | zebra | tree
+        Verb --> sees
+        Prep --> under
+    ";
+
+    fn sentence(pps: usize) -> Vec<&'static str> {
+        let mut sentence = vec!("a", "lion", "sees", "a", "zebra");
+        for _ in 0..pps {
+            sentence.extend(&["under", "a", "tree"]);
+        }
+        sentence
+    }
+
+    fn sorted(trees: impl Iterator<Item = Tree>) -> Vec<String> {
+        let mut trees: Vec<String> = trees.map(|tree| tree.to_string()).collect();
+        trees.sort();
+        trees
+    }
+
+    #[test]
+    fn holds_the_trees_of_extract_tree() {
+        let grammar: Grammar = GRAMMAR.parse().unwrap();
+        for pps in 0..4 {
+            let sentence = sentence(pps);
+
+            let forest = parse_forest(&grammar.rules, "S", &sentence);
+
+            let trees = parse(&grammar.rules, "S", &sentence);
+            assert_eq!(forest.count_trees(), trees.len() as u64);
+            assert_eq!(sorted(forest.trees()), sorted(trees.iter().cloned()));
+        }
+    }
+
+    #[test]
+    fn packs_exponentially_many_trees() {
+        let grammar: Grammar = GRAMMAR.parse().unwrap();
+        let sentence = sentence(20);
+        let chart = earley(&grammar.rules, &sentence);
+
+        let forest = Forest::new(&chart, "S");
+
+        assert_eq!(forest.count_trees(), count_trees(&chart, "S"));
+        // The Catalan number C(21)
+        assert_eq!(forest.count_trees(), 24_466_267_020);
+        assert!(forest.nodes().len() < 2 * sentence.len() * sentence.len());
+        let last = forest.tree(forest.count_trees() - 1).unwrap();
+        assert_ne!(last, forest.tree(0).unwrap());
+        assert_eq!(forest.nodes()[forest.root().unwrap()].label, Label::Symbol { lhs: "S", start: 0, end: 65 });
+    }
+
+    #[test]
+    fn shares_sequences_and_empty_constituents() {
+        let grammar: Grammar = "
+            S --> A B C | A B D
+            A --> a |
+            B --> b
+            C --> c
+            D --> c
+        ".parse().unwrap();
+
+        let forest = parse_forest(&grammar.rules, "S", &["b", "c"]);
+
+        let sequences: Vec<&ForestNode> = forest.nodes().iter()
+            .filter(|node| matches!(node.label, Label::Sequence { .. }))
+            .collect();
+        assert_eq!(sequences.len(), 1);
+        assert_eq!(sequences[0].label, Label::Sequence { rhs: vec!("A", "B"), start: 0, end: 1 });
+        assert_eq!(sorted(forest.trees()), vec!("S[A B[b] C[c]]", "S[A B[b] D[c]]"));
+        assert_eq!(parse_forest(&grammar.rules, "S", &["a", "c"]).count_trees(), 0);
+        assert_eq!(parse_forest(&grammar.rules, "S", &["a", "c"]).tree(0), None);
+    }
+
+    #[test]
+    fn leaves_out_cycles_whatever_the_order() {
+        for grammar in &["S --> A | B\nA --> B | a\nB --> A | a", "S --> B | A\nA --> B | a\nB --> A | a"] {
+            let grammar: Grammar = grammar.parse().unwrap();
+            let chart = earley(&grammar.rules, &["a"]);
+
+            let forest = Forest::new(&chart, "S");
+
+            assert_eq!(forest.count_trees(), count_trees(&chart, "S"));
+            assert_eq!(sorted(forest.trees()), vec!("S[A[B[a]]]", "S[A[a]]", "S[B[A[a]]]", "S[B[a]]"));
+        }
+    }
+
+    #[test]
+    fn extracts_trees_of_saturated_counts() {
+        let grammar: Grammar = "S --> A B\nA --> A A | a\nB --> B B | b".parse().unwrap();
+        let mut sentence = vec!("a"; 40);
+        sentence.extend(&["b"; 40]);
+
+        let forest = parse_forest(&grammar.rules, "S", &sentence);
+
+        // Both A and B have the Catalan number C(39) > 2^64 trees
+        assert_eq!(forest.count_trees(), u64::MAX);
+        let first = forest.tree(0).unwrap();
+        let last = forest.tree(u64::MAX - 1).unwrap();
+        assert_ne!(first, last);
+        assert_ne!(forest.tree(1).unwrap(), first);
+        assert_eq!(first.to_string().matches('a').count(), 40);
+        assert_eq!(last.to_string().matches('b').count(), 40);
+        assert!(forest.tree(u64::MAX).is_none());
+    }
+}
@@ -1,2 +1,8 @@
 pub mod print;
 pub mod chart_parsing;
+pub mod cky_parsing;
+pub mod semiring_parsing;
+pub mod training;
+pub mod forest;
+#[cfg(feature = "arena")]
+pub mod arena_parsing;
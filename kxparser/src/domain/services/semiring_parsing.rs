@@ -0,0 +1,540 @@
+use std::collections::{BTreeSet, HashMap};
+
+use ds_13::unsync::list::{concat, List};
+use crate::domain::models::{Rule, Edge, Chart};
+use crate::domain::models::grammar::rule_probabilities;
+use crate::domain::services::chart_parsing::{earley, topdown_map, TopdownMap, Tree};
+
+/// The values that `semiring_parse` combines: `plus` over the
+/// alternative analyses of a constituent and `times` over the
+/// children of one analysis.
+pub trait Semiring: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn plus(&self, other: &Self) -> Self;
+    fn times(&self, other: &Self) -> Self;
+}
+
+/// Whether there is an analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Boolean(pub bool);
+
+/// The number of analyses, saturating at `u64::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Counting(pub u64);
+
+/// The probability of the best analysis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viterbi(pub f64);
+
+/// The total probability of all analyses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inside(pub f64);
+
+/// The `K` most probable analyses, best first, as the probability
+/// and the trees of each.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KBest<const K: usize>(pub Vec<(f64, List<Tree>)>);
+
+impl Semiring for Boolean {
+    fn zero() -> Self {
+        Boolean(false)
+    }
+
+    fn one() -> Self {
+        Boolean(true)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        Boolean(self.0 || other.0)
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        Boolean(self.0 && other.0)
+    }
+}
+
+impl Semiring for Counting {
+    fn zero() -> Self {
+        Counting(0)
+    }
+
+    fn one() -> Self {
+        Counting(1)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        Counting(self.0.saturating_add(other.0))
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        Counting(self.0.saturating_mul(other.0))
+    }
+}
+
+impl Semiring for Viterbi {
+    fn zero() -> Self {
+        Viterbi(0.0)
+    }
+
+    fn one() -> Self {
+        Viterbi(1.0)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        Viterbi(self.0.max(other.0))
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        Viterbi(self.0 * other.0)
+    }
+}
+
+impl Semiring for Inside {
+    fn zero() -> Self {
+        Inside(0.0)
+    }
+
+    fn one() -> Self {
+        Inside(1.0)
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        Inside(self.0 + other.0)
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        Inside(self.0 * other.0)
+    }
+}
+
+impl<const K: usize> KBest<K> {
+    fn best(mut items: Vec<(f64, List<Tree>)>) -> Self {
+        items.sort_by(|(x, _), (y, _)| y.partial_cmp(x).unwrap_or(std::cmp::Ordering::Equal));
+        items.truncate(K);
+        KBest(items)
+    }
+
+    /// Returns the analyses as constituents for `edge`, weighted by
+    /// `weight`.
+    pub fn node(&self, edge: &Edge, weight: f64) -> Self {
+        KBest(
+            self.0.iter()
+                .map(|(p, children)| (p * weight, List::from_value(Tree::new(edge.lhs, children.clone()))))
+                .collect()
+        )
+    }
+}
+
+impl<const K: usize> Semiring for KBest<K> {
+    fn zero() -> Self {
+        KBest(Vec::new())
+    }
+
+    fn one() -> Self {
+        KBest(vec!((1.0, List::new())))
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        KBest::best(self.0.iter().chain(&other.0).cloned().collect())
+    }
+
+    fn times(&self, other: &Self) -> Self {
+        let mut items = Vec::new();
+        for (p, xs) in &self.0 {
+            for (q, ys) in &other.0 {
+                items.push((p * q, concat(xs, ys)));
+            }
+        }
+        KBest::best(items)
+    }
+}
+
+/// Computes the value of `cat` over the whole input of `chart`.
+///
+/// The children of every passive edge are combined with `times`,
+/// `value` turns the result into the value of the constituent, and
+/// the constituents with the same lhs and span are combined with
+/// `plus`. Without unit or empty cycles, every constituent is computed
+/// once, so this takes polynomial time even when the number of trees
+/// is exponential.
+///
+/// Trees where a constituent contains itself, like `A --> B`,
+/// `B --> A` over the same span, are left out. The constituents on
+/// such cycles are computed again every time they are reached, since
+/// what is left out depends on the constituents above them.
+///
+/// # Examples
+///
+/// ```
+/// use kxparser::domain::models::Grammar;
+/// use kxparser::domain::services::chart_parsing::earley;
+/// use kxparser::domain::services::semiring_parsing::{semiring_parse, Semiring, Counting};
+///
+/// let grammar: Grammar = "S --> S S | a".parse().unwrap();
+/// let chart = earley(&grammar.rules, &["a"; 6]);
+///
+/// let count = semiring_parse(&chart, "S", |_edge, children: Counting| children);
+///
+/// assert_eq!(count, Counting(42));
+/// ```
+pub fn semiring_parse<'a, S: Semiring>(
+    chart: &Chart<'a>,
+    cat: &'a str,
+    value: impl Fn(&Edge<'a>, S) -> S,
+) -> S {
+    let topdowns = topdown_map(chart);
+    let mut evaluator = Evaluator::new(&topdowns, value);
+    evaluator.constituent(cat, 0, chart.chart.len() - 1)
+}
+
+pub fn recognize<'a>(chart: &Chart<'a>, cat: &'a str) -> bool {
+    semiring_parse(chart, cat, |_, children: Boolean| children).0
+}
+
+pub fn count_trees<'a>(chart: &Chart<'a>, cat: &'a str) -> u64 {
+    semiring_parse(chart, cat, |_, children: Counting| children).0
+}
+
+/// Returns the probability of the best tree, where `weight` gives
+/// the probability of the rule of an edge.
+pub fn best_score<'a>(chart: &Chart<'a>, cat: &'a str, weight: impl Fn(&Edge<'a>) -> f64) -> f64 {
+    semiring_parse(chart, cat, |edge, children: Viterbi| Viterbi(weight(edge)).times(&children)).0
+}
+
+/// Returns the total probability of the trees, where `weight` gives
+/// the probability of the rule of an edge.
+pub fn inside_probability<'a>(chart: &Chart<'a>, cat: &'a str, weight: impl Fn(&Edge<'a>) -> f64) -> f64 {
+    semiring_parse(chart, cat, |edge, children: Inside| Inside(weight(edge)).times(&children)).0
+}
+
+/// Returns the `K` most probable trees, best first, with their
+/// probabilities.
+pub fn k_best<'a, const K: usize>(
+    chart: &Chart<'a>,
+    cat: &'a str,
+    weight: impl Fn(&Edge<'a>) -> f64,
+) -> Vec<(f64, Tree)> {
+    let best = semiring_parse(chart, cat, |edge, children: KBest<K>| children.node(edge, weight(edge)));
+    best.0.into_iter()
+        .filter_map(|(p, trees)| trees.front().map(|tree| (p, tree.clone())))
+        .collect()
+}
+
+/// Returns the most probable tree of `sentence` and its probability,
+/// using the probabilities of the rules from `rule_probabilities`.
+///
+/// # Examples
+///
+/// ```
+/// use kxparser::domain::models::Grammar;
+/// use kxparser::domain::services::semiring_parsing::{viterbi_parse, sentence_probability};
+///
+/// let grammar: Grammar = "
+///     S --> NP VP
+///     NP --> NP PP [0.2] | Det N [0.8]
+///     VP --> V NP [0.6] | VP PP [0.4]
+///     PP --> P NP
+///     Det --> a
+///     N --> lion | zebra | tree
+///     V --> sees
+///     P --> under
+/// ".parse().unwrap();
+/// let sentence = ["a", "lion", "sees", "a", "zebra", "under", "a", "tree"];
+///
+/// let (tree, p) = viterbi_parse(&grammar.rules, "S", &sentence).unwrap();
+///
+/// assert!(tree.to_string().starts_with("S[NP[Det[a] N[lion]] VP[VP["));
+/// assert!((sentence_probability(&grammar.rules, "S", &sentence) - p) > 0.0);
+/// ```
+pub fn viterbi_parse<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> Option<(Tree, f64)> {
+    let chart = earley(grammar, sentence);
+    let weights = rule_weights(grammar);
+    k_best::<1>(&chart, cat, |edge| weight(&weights, edge))
+        .into_iter()
+        .next()
+        .map(|(p, tree)| (tree, p))
+}
+
+/// Returns the total probability of the trees of `sentence`, using
+/// the probabilities of the rules from `rule_probabilities`.
+pub fn sentence_probability<'a>(grammar: &'a [Rule], cat: &'a str, sentence: &[&'a str]) -> f64 {
+    let chart = earley(grammar, sentence);
+    let weights = rule_weights(grammar);
+    inside_probability(&chart, cat, |edge| weight(&weights, edge))
+}
+
+/// Returns the probabilities of the rules by lhs and rhs, summing
+/// those of duplicate rules.
+pub(crate) fn rule_weights(grammar: &[Rule]) -> HashMap<(&str, Vec<&str>), f64> {
+    let mut weights = HashMap::new();
+    for (rule, probability) in grammar.iter().zip(rule_probabilities(grammar)) {
+        let rhs = rule.rhs.iter().map(String::as_str).collect();
+        *weights.entry((rule.lhs.as_str(), rhs)).or_insert(0.0) += probability;
+    }
+    weights
+}
+
+/// Returns the probability of the rule of `edge`, which is 1 for words.
+pub(crate) fn weight(weights: &HashMap<(&str, Vec<&str>), f64>, edge: &Edge) -> f64 {
+    weights.get(&(edge.lhs, edge.rhs.clone())).copied().unwrap_or(1.0)
+}
+
+/// A computation of the value of a constituent by `Evaluator`.
+pub(crate) struct Pass<'t, 'a, S> {
+    pub(crate) value: S,
+    /// The edges of the constituent, with the passes of the children
+    /// of every sequence of constituents for their rhs.
+    pub(crate) edges: Vec<(&'t Edge<'a>, Vec<Vec<usize>>)>,
+}
+
+pub(crate) struct Evaluator<'t, 'a, S, F> {
+    topdowns: &'t TopdownMap<'a>,
+    value: F,
+    /// The values by lhs and span of the constituents that aren't on
+    /// a cycle, with their pass.
+    constituents: HashMap<(&'a str, usize, usize), (S, Option<usize>)>,
+    /// The constituents that are being computed, by their depth.
+    active: HashMap<(&'a str, usize, usize), usize>,
+    /// Every computation of a constituent, after those of its children,
+    /// if they are recorded. The constituents on a cycle have a pass
+    /// wherever they are reached.
+    pub(crate) passes: Option<Vec<Pass<'t, 'a, S>>>,
+}
+
+impl<'t, 'a, S, F> Evaluator<'t, 'a, S, F>
+where
+    S: Semiring,
+    F: Fn(&Edge<'a>, S) -> S,
+{
+    pub(crate) fn new(topdowns: &'t TopdownMap<'a>, value: F) -> Self {
+        Evaluator {
+            topdowns,
+            value,
+            constituents: HashMap::new(),
+            active: HashMap::new(),
+            passes: None,
+        }
+    }
+
+    /// Like `new`, but records the passes.
+    pub(crate) fn recording(topdowns: &'t TopdownMap<'a>, value: F) -> Self {
+        Evaluator { passes: Some(Vec::new()), ..Evaluator::new(topdowns, value) }
+    }
+
+    pub(crate) fn constituent(&mut self, lhs: &'a str, start: usize, end: usize) -> S {
+        self.evaluate(lhs, start, end).0
+    }
+
+    /// Returns the value of a constituent, the least depth of the
+    /// constituents being computed that the value depends on, which
+    /// is `usize::MAX` if it depends on none of them, and its pass if
+    /// the passes are recorded and it isn't on a cycle being computed.
+    fn evaluate(&mut self, lhs: &'a str, start: usize, end: usize) -> (S, usize, Option<usize>) {
+        let key = (lhs, start, end);
+        if let Some((value, pass)) = self.constituents.get(&key) {
+            return (value.clone(), usize::MAX, *pass);
+        }
+        // A cycle, which is left out
+        if let Some(depth) = self.active.get(&key) {
+            return (S::zero(), *depth, None);
+        }
+        let depth = self.active.len();
+        self.active.insert(key, depth);
+        let topdowns = self.topdowns;
+        let mut total = S::zero();
+        let mut low = usize::MAX;
+        let mut edges = Vec::new();
+        for edge in topdowns.get_all(&(lhs, start)).filter(|edge| edge.end == end) {
+            // Words and empty constituents have no children
+            let (children, splits) = match edge.rhs.is_empty() {
+                true => (S::one(), vec!(Vec::new())),
+                false => match self.children(&edge.rhs, start, end) {
+                    Some((children, depth, splits)) => {
+                        low = low.min(depth);
+                        (children, splits)
+                    }
+                    None => continue,
+                },
+            };
+            total = total.plus(&(self.value)(edge, children));
+            if self.passes.is_some() {
+                edges.push((edge, splits));
+            }
+        }
+        self.active.remove(&key);
+        let pass = self.passes.as_mut().map(|passes| {
+            passes.push(Pass { value: total.clone(), edges });
+            passes.len() - 1
+        });
+        // Without cycles through this constituent or the ones above
+        // it, the value is the same wherever it is reached
+        if low > depth {
+            self.constituents.insert(key, (total.clone(), pass));
+        }
+        (total, low, pass)
+    }
+
+    /// Returns the value of the sequences of constituents for `rhs`,
+    /// like `evaluate`, with the passes of the children of every
+    /// sequence if they are recorded, or `None` if the chart has no
+    /// sequence. The rest of `rhs` is computed first, so that left
+    /// recursion that can't be completed isn't taken for a cycle.
+    fn children(&mut self, rhs: &[&'a str], start: usize, end: usize) -> Option<(S, usize, Vec<Vec<usize>>)> {
+        match rhs.split_first() {
+            None if start == end => Some((S::one(), usize::MAX, vec!(Vec::new()))),
+            None => None,
+            Some((first, rest)) => {
+                let topdowns = self.topdowns;
+                let mids: BTreeSet<usize> = topdowns.get_all(&(*first, start))
+                    .map(|edge| edge.end)
+                    .filter(|mid| *mid <= end)
+                    .collect();
+                let mut result: Option<(S, usize, Vec<Vec<usize>>)> = None;
+                for mid in mids {
+                    let (rest, rest_low, rest_splits) = match self.children(rest, mid, end) {
+                        Some(rest) => rest,
+                        None => continue,
+                    };
+                    let (first, first_low, first_pass) = self.evaluate(first, start, mid);
+                    let (total, low, mut splits) = result.unwrap_or((S::zero(), usize::MAX, Vec::new()));
+                    // The sequences through a cycle are left out
+                    if let Some(pass) = first_pass {
+                        splits.extend(rest_splits.into_iter().map(|mut split| {
+                            split.insert(0, pass);
+                            split
+                        }));
+                    }
+                    result = Some((total.plus(&first.times(&rest)), low.min(first_low).min(rest_low), splits));
+                }
+                result
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Grammar;
+    use crate::domain::services::chart_parsing::extract_tree;
+
+    const GRAMMAR: &str = "
+        S --> NP VP
+        NP --> Det Noun | NP PP
+        VP --> Verb NP | VP PP
+        PP --> Prep NP
+        Det --> a
+        Noun --> lion | zebra | tree | telescope | park
+        Verb --> sees
+        Prep --> under | with | in
+    ";
+
+    fn sentence(pps: usize) -> Vec<&'static str> {
+        let mut sentence = vec!("a", "lion", "sees", "a", "zebra");
+        for (prep, noun) in ["under", "with", "in"].iter().zip(&["tree", "telescope", "park"]).take(pps) {
+            sentence.extend(&[*prep, "a", *noun]);
+        }
+        sentence
+    }
+
+    /// Prefers attaching PPs to verb phrases.
+    fn weight(edge: &Edge) -> f64 {
+        match (edge.lhs, edge.rhs.as_slice()) {
+            ("NP", ["NP", "PP"]) => 0.2,
+            ("VP", ["VP", "PP"]) => 0.5,
+            _ => 1.0,
+        }
+    }
+
+    #[test]
+    fn counts_trees_like_extract_tree() {
+        let grammar: Grammar = GRAMMAR.parse().unwrap();
+        for pps in 0..4 {
+            let sentence = sentence(pps);
+            let chart = earley(&grammar.rules, &sentence);
+
+            let count = count_trees(&chart, "S");
+
+            assert!(recognize(&chart, "S"));
+            assert_eq!(count, extract_tree(chart, "S").len() as u64);
+        }
+        let chart = earley(&grammar.rules, &["a", "lion", "sees"]);
+        assert!(!recognize(&chart, "S"));
+        assert_eq!(count_trees(&chart, "S"), 0);
+    }
+
+    #[test]
+    fn scores_trees() {
+        let grammar: Grammar = GRAMMAR.parse().unwrap();
+        let chart = earley(&grammar.rules, &sentence(1));
+
+        assert_eq!(best_score(&chart, "S", weight), 0.5);
+        assert!((inside_probability(&chart, "S", weight) - 0.7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn finds_k_best_trees() {
+        let grammar: Grammar = GRAMMAR.parse().unwrap();
+        let chart = earley(&grammar.rules, &sentence(2));
+
+        let best = k_best::<3>(&chart, "S", weight);
+        let all = k_best::<10>(&chart, "S", weight);
+
+        assert_eq!(best.len(), 3);
+        assert_eq!(all.len(), 5);
+        assert_eq!(best[0].0, best_score(&chart, "S", weight));
+        assert!(best.windows(2).all(|pair| pair[0].0 >= pair[1].0));
+        assert_eq!(
+            best[0].1.to_string(),
+            "S[NP[Det[a] Noun[lion]] VP[VP[VP[Verb[sees] NP[Det[a] Noun[zebra]]] \
+             PP[Prep[under] NP[Det[a] Noun[tree]]]] PP[Prep[with] NP[Det[a] Noun[telescope]]]]]"
+        );
+        let mut trees: Vec<String> = all.iter().map(|(_, tree)| tree.to_string()).collect();
+        let mut expected: Vec<String> = extract_tree(chart, "S").iter().map(|tree| tree.to_string()).collect();
+        trees.sort();
+        expected.sort();
+        assert_eq!(trees, expected);
+    }
+
+    #[test]
+    fn leaves_out_cycles_whatever_the_order() {
+        for grammar in &["S --> A | B\nA --> B | a\nB --> A | a", "S --> B | A\nA --> B | a\nB --> A | a"] {
+            let grammar: Grammar = grammar.parse().unwrap();
+            let chart = earley(&grammar.rules, &["a"]);
+
+            let trees = k_best::<10>(&chart, "S", |_| 1.0);
+
+            assert_eq!(count_trees(&chart, "S"), 4);
+            let mut trees: Vec<String> = trees.iter().map(|(_, tree)| tree.to_string()).collect();
+            trees.sort();
+            assert_eq!(trees, vec!("S[A[B[a]]]", "S[A[a]]", "S[B[A[a]]]", "S[B[a]]"));
+        }
+    }
+
+    #[test]
+    fn uses_rule_weights() {
+        let grammar: Grammar = "
+            S --> NP VP
+            NP --> NP PP [0.2] | Det Noun [0.8]
+            VP --> Verb NP [0.7] | VP PP [0.3]
+            PP --> Prep NP
+            Det --> a
+            Noun --> lion [0.5] | zebra [0.25] | tree [0.25]
+            Verb --> sees
+            Prep --> under
+        ".parse().unwrap();
+        let sentence = sentence(1);
+
+        let (tree, p) = viterbi_parse(&grammar.rules, "S", &sentence).unwrap();
+
+        let (a_lion, a_zebra, a_tree) = (0.8 * 0.5, 0.8 * 0.25, 0.8 * 0.25);
+        let vp_attachment = a_lion * 0.3 * 0.7 * a_zebra * a_tree;
+        let np_attachment = a_lion * 0.7 * 0.2 * a_zebra * a_tree;
+        assert!((p - vp_attachment).abs() < 1e-12);
+        assert!(tree.to_string().starts_with("S[NP[Det[a] Noun[lion]] VP[VP["));
+        assert!((sentence_probability(&grammar.rules, "S", &sentence) - vp_attachment - np_attachment).abs() < 1e-12);
+        assert_eq!(viterbi_parse(&grammar.rules, "S", &["a", "lion"]), None);
+        assert_eq!(sentence_probability(&grammar.rules, "S", &["a", "lion"]), 0.0);
+    }
+}
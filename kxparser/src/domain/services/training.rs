@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::io;
+
+use crate::domain::models::{Grammar, Rule, Edge};
+use crate::domain::models::grammar::rule_probabilities;
+use crate::domain::services::chart_parsing::{earley, topdown_map};
+use crate::domain::services::semiring_parsing::{rule_weights, weight, Evaluator, Inside, Semiring};
+
+/// Runs `iterations` steps of `em_step`, and returns the trained
+/// grammar with the corpus log-likelihood before every step.
+///
+/// # Examples
+///
+/// ```
+/// use kxparser::domain::models::Grammar;
+/// use kxparser::domain::services::training::train;
+///
+/// let grammar: Grammar = "S --> S S | a".parse().unwrap();
+/// let corpus = vec!(vec!("a"), vec!("a", "a"), vec!("a", "a", "a"));
+///
+/// let (trained, log_likelihoods) = train(&grammar, "S", &corpus, 5);
+///
+/// assert!(log_likelihoods.windows(2).all(|pair| pair[0] <= pair[1] + 1e-12));
+/// assert!(trained.rules[1].probability() > 0.5);
+/// ```
+pub fn train(grammar: &Grammar, cat: &str, corpus: &[Vec<&str>], iterations: usize) -> (Grammar, Vec<f64>) {
+    let mut grammar = grammar.clone();
+    let mut log_likelihoods = Vec::new();
+    for _ in 0..iterations {
+        let (trained, log_likelihood) = em_step(&grammar, cat, corpus);
+        grammar = trained;
+        log_likelihoods.push(log_likelihood);
+    }
+    (grammar, log_likelihoods)
+}
+
+/// Re-estimates the rule probabilities from the expected number of
+/// times every rule is used in the trees of the sentences, computed
+/// with inside and outside probabilities over the `earley` chart.
+/// Returns the new grammar and the corpus log-likelihood under the
+/// old one.
+///
+/// The rules start out with the probabilities of `rule_probabilities`,
+/// like in `sentence_probability`. Sentences without a tree are
+/// skipped, and a lhs that is never used keeps the probabilities of
+/// its rules. Duplicate rules build the same edges, whose probability
+/// is the sum of theirs, and share their expected counts in proportion
+/// to their probabilities. Like in `sentence_probability`, the trees
+/// where a constituent contains itself, like with `A --> B`,
+/// `B --> A`, are left out.
+pub fn em_step(grammar: &Grammar, cat: &str, corpus: &[Vec<&str>]) -> (Grammar, f64) {
+    let probabilities = rule_probabilities(&grammar.rules);
+    let weights = rule_weights(&grammar.rules);
+    // The indices of the equal rules for every lhs and rhs
+    let mut duplicates: Vec<Vec<usize>> = Vec::new();
+    let mut index: HashMap<(&str, Vec<&str>), usize> = HashMap::new();
+    for (n, rule) in grammar.rules.iter().enumerate() {
+        let key = (rule.lhs.as_str(), rule.rhs.iter().map(String::as_str).collect());
+        let group = *index.entry(key).or_insert_with(|| {
+            duplicates.push(Vec::new());
+            duplicates.len() - 1
+        });
+        duplicates[group].push(n);
+    }
+    let rules_of = |edge: &Edge| index.get(&(edge.lhs, edge.rhs.clone())).map(|group| &duplicates[*group]);
+
+    let mut counts = vec!(0.0; grammar.rules.len());
+    let mut log_likelihood = 0.0;
+    for sentence in corpus {
+        let chart = earley(&grammar.rules, sentence);
+        let topdowns = topdown_map(&chart);
+        let mut evaluator = Evaluator::recording(&topdowns, |edge, children: Inside| {
+            Inside(weight(&weights, edge)).times(&children)
+        });
+        let total = evaluator.constituent(cat, 0, sentence.len()).0;
+        if total <= 0.0 {
+            continue;
+        }
+        log_likelihood += total.ln();
+
+        // Constituents on a cycle have a pass with its own inside and
+        // outside probabilities wherever they are reached
+        let passes = evaluator.passes.unwrap_or_default();
+        let mut outside = vec!(0.0; passes.len());
+        outside[passes.len() - 1] = 1.0;
+        // Parents come before their children
+        for (n, pass) in passes.iter().enumerate().rev() {
+            let parent = outside[n];
+            if parent == 0.0 {
+                continue;
+            }
+            for (edge, splits) in &pass.edges {
+                let rules = match rules_of(edge) {
+                    Some(rules) => rules,
+                    None => continue,
+                };
+                let probability = weight(&weights, edge);
+                for children in splits {
+                    let insides: Vec<f64> = children.iter().map(|child| passes[*child].value.0).collect();
+                    let product = insides.iter().product::<f64>();
+                    for n in rules {
+                        counts[*n] += parent * probabilities[*n] * product / total;
+                    }
+                    for (i, child) in children.iter().enumerate() {
+                        let siblings: f64 = insides.iter()
+                            .enumerate()
+                            .filter(|(j, _)| *j != i)
+                            .map(|(_, x)| x)
+                            .product();
+                        outside[*child] += parent * probability * siblings;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut totals: HashMap<&str, f64> = HashMap::new();
+    for (rule, count) in grammar.rules.iter().zip(&counts) {
+        *totals.entry(&rule.lhs).or_insert(0.0) += count;
+    }
+    let rules = grammar.rules.iter()
+        .zip(counts.iter().zip(&probabilities))
+        .map(|(rule, (count, probability))| {
+            let total = totals[rule.lhs.as_str()];
+            let weight = if total > 0.0 { count / total } else { *probability };
+            Rule::weighted(&rule.lhs, rule.rhs.clone(), weight)
+        })
+        .collect();
+    (Grammar::from_rules(rules), log_likelihood)
+}
+
+/// Writes the rules in the RON format that `chart-parser` reads.
+pub fn write_ron(grammar: &Grammar, writer: impl io::Write) -> ron::Result<()> {
+    ron::ser::to_writer(writer, &grammar.rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::semiring_parsing::sentence_probability;
+
+    fn weights(grammar: &Grammar) -> Vec<f64> {
+        grammar.rules.iter().map(Rule::probability).collect()
+    }
+
+    #[test]
+    fn estimates_relative_frequencies() {
+        let grammar: Grammar = "S --> a | b".parse().unwrap();
+        let corpus = vec!(vec!("a"), vec!("a"), vec!("b"), vec!("a"));
+
+        let (trained, log_likelihoods) = train(&grammar, "S", &corpus, 2);
+
+        assert_eq!(weights(&trained), vec!(0.75, 0.25));
+        assert!((log_likelihoods[0] - 4.0 * 0.5f64.ln()).abs() < 1e-12);
+        assert!((log_likelihoods[1] - 3.0 * 0.75f64.ln() - 0.25f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn splits_counts_of_ambiguous_sentences() {
+        let grammar: Grammar = "
+            S --> NP VP
+            NP --> NP PP | Det Noun
+            VP --> Verb NP | VP PP
+            PP --> Prep NP
+            Det --> a
+            Noun --> lion | zebra | tree
+            Verb --> sees
+            Prep --> under
+        ".parse().unwrap();
+        let corpus = vec!(
+            "a lion sees a zebra under a tree".split(' ').collect(),
+            "a lion sees a zebra".split(' ').collect(),
+            "a tree".split(' ').collect(),
+        );
+
+        let (trained, log_likelihoods) = train(&grammar, "S", &corpus, 10);
+
+        assert!(log_likelihoods.windows(2).all(|pair| pair[0] <= pair[1] + 1e-12));
+        for lhs in &["S", "NP", "VP", "Noun"] {
+            let total: f64 = trained.rules.iter().filter(|rule| rule.lhs == *lhs).map(Rule::probability).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+        // Two lions, two zebras and a tree in the sentences, `a tree` isn't one
+        for (weight, expected) in weights(&trained)[7..].iter().zip(&[0.4, 0.4, 0.2]) {
+            assert!((weight - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn keeps_weights_of_unused_rules() {
+        let grammar: Grammar = "S --> a [0.9] | b\nT --> c [0.3] | d".parse().unwrap();
+
+        let (trained, _) = em_step(&grammar, "S", &[vec!("a")]);
+
+        assert_eq!(weights(&trained)[..2], [1.0, 0.0]);
+        assert!((weights(&trained)[3] - 0.7).abs() < 1e-12);
+        assert_eq!(trained.rules[2].weight, Some(0.3));
+    }
+
+    #[test]
+    fn duplicate_rules_share_counts() {
+        let grammar: Grammar = "S --> a | b | a".parse().unwrap();
+        let corpus = vec!(vec!("a"), vec!("a"), vec!("b"), vec!("a"));
+
+        let (trained, log_likelihoods) = train(&grammar, "S", &corpus, 2);
+
+        assert_eq!(weights(&trained), vec!(0.375, 0.25, 0.375));
+        assert!((log_likelihoods[0] - 3.0 * (2.0f64 / 3.0).ln() - (1.0f64 / 3.0).ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn counts_rules_of_unit_cycles() {
+        let grammar: Grammar = "S --> A | b\nA --> B | a\nB --> A | a".parse().unwrap();
+
+        let (trained, _) = em_step(&grammar, "S", &[vec!("a"), vec!("b")]);
+
+        // `a` has the trees S[A[a]] and S[A[B[a]]], with 1/4 and 1/8
+        let expected = [0.5, 0.5, 1.0 / 3.0, 2.0 / 3.0, 0.0, 1.0];
+        for (weight, expected) in weights(&trained).iter().zip(&expected) {
+            assert!((weight - expected).abs() < 1e-12);
+        }
+
+        // A and B are on the cycle below S[A[B[a]]] and S[B[A[a]]]
+        let grammar: Grammar = "S --> A | B\nA --> B | a\nB --> A | a".parse().unwrap();
+
+        let (trained, _) = em_step(&grammar, "S", &[vec!("a")]);
+
+        for (weight, expected) in weights(&trained).iter().zip(&[0.5, 0.5, 0.25, 0.75, 0.25, 0.75]) {
+            assert!((weight - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn log_likelihood_agrees_with_sentence_probability() {
+        let grammar: Grammar = "
+            S --> NP VP | S and S [0.2]
+            NP --> Det N | NP PP [0.3] | Det N
+            VP --> V NP [0.6] | VP PP | V
+            PP --> P NP
+            Det --> a
+            N --> lion | zebra [0.5]
+            V --> sees
+            P --> under
+        ".parse().unwrap();
+        let corpus: Vec<Vec<&str>> = vec!(
+            "a lion sees a zebra under a lion".split(' ').collect(),
+            "a zebra sees and a lion sees a zebra".split(' ').collect(),
+            "a lion sees".split(' ').collect(),
+        );
+
+        let (_, log_likelihoods) = train(&grammar, "S", &corpus, 1);
+
+        let expected: f64 = corpus.iter()
+            .map(|sentence| sentence_probability(&grammar.rules, "S", sentence).ln())
+            .sum();
+        assert!(expected.is_finite());
+        assert!((log_likelihoods[0] - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn writes_ron() {
+        let grammar: Grammar = "S --> a [0.5] | b".parse().unwrap();
+        let mut ron = Vec::new();
+
+        write_ron(&grammar, &mut ron).unwrap();
+
+        let ron = String::from_utf8(ron).unwrap();
+        assert_eq!(ron, r#"[(lhs:"S",rhs:["a"],weight:Some(0.5)),(lhs:"S",rhs:["b"])]"#);
+        let rules: Vec<Rule> = ron::de::from_str(&ron).unwrap();
+        assert_eq!(rules, grammar.rules);
+    }
+}
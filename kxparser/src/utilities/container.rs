@@ -1,11 +1,71 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::domain::models::Rule;
 
+/// Returns the rules by the first symbol of their rhs. Rules with an
+/// empty rhs have no left corner and are left out.
 pub fn leftcorners_dict<'a>(grammar: &'a [Rule]) -> HashMap<&'a str, Vec<&Rule>> {
     let mut leftcorners = HashMap::new();
     for rule in grammar {
-        let entry = leftcorners.entry(rule.rhs[0].as_str()).or_insert(Vec::new());
-        entry.push(rule);
+        if let Some(leftcorner) = rule.rhs.first() {
+            leftcorners.entry(leftcorner.as_str()).or_insert(Vec::new()).push(rule);
+        }
     }
     leftcorners
 }
+
+/// Returns the rules by their lhs.
+pub fn rules_dict(grammar: &[Rule]) -> HashMap<&str, Vec<&Rule>> {
+    let mut rules = HashMap::new();
+    for rule in grammar {
+        rules.entry(rule.lhs.as_str()).or_insert(Vec::new()).push(rule);
+    }
+    rules
+}
+
+/// Returns the symbols that derive the empty string.
+pub fn nullable_set(grammar: &[Rule]) -> HashSet<&str> {
+    let mut nullable = HashSet::new();
+    loop {
+        let size = nullable.len();
+        for rule in grammar {
+            if rule.rhs.iter().all(|x| nullable.contains(x.as_str())) {
+                nullable.insert(rule.lhs.as_str());
+            }
+        }
+        if nullable.len() == size {
+            return nullable;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leftcorners_dict_skips_empty_rules() {
+        let grammar = vec!(
+            Rule::new("S", vec!("NP".to_string(), "VP".to_string())),
+            Rule::new("NP", Vec::new()),
+        );
+
+        let leftcorners = leftcorners_dict(&grammar);
+
+        assert_eq!(leftcorners.len(), 1);
+        assert_eq!(leftcorners["NP"][0].lhs, "S");
+    }
+
+    #[test]
+    fn nullable_set_follows_chains() {
+        let grammar = vec!(
+            Rule::new("A", vec!("B".to_string(), "C".to_string())),
+            Rule::new("B", vec!("C".to_string())),
+            Rule::new("C", Vec::new()),
+            Rule::new("D", vec!("C".to_string(), "d".to_string())),
+        );
+
+        let nullable = nullable_set(&grammar);
+
+        assert_eq!(nullable, vec!("A", "B", "C").into_iter().collect());
+    }
+}